@@ -0,0 +1,323 @@
+//! Runtime state of the app that is not a setting: click-through, the
+//! current card and session, the AI provider and the main window. Every
+//! change is broadcast to all windows as one `state-changed` event carrying
+//! the whole state, so listeners never have to merge partial updates.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::Manager;
+
+use crate::timers::Timers;
+
+/// How long each card stays on screen before the next one.
+const CARD_DISPLAY_TIME: Duration = Duration::from_secs(10);
+const CARD_TIMER: &str = "card-rotation";
+
+/// Reachability of the configured AI provider.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ProviderStatus {
+    #[default]
+    Unknown,
+    Online,
+    Offline,
+    Generating,
+}
+
+/// Whether the main window is on screen.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum WindowMode {
+    #[default]
+    Visible,
+    Hidden,
+}
+
+/// The card on the main window.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CurrentCard {
+    pub index: usize,
+    pub text: String,
+    /// Known once the card's details have been looked up
+    #[serde(default)]
+    pub translation: Option<String>,
+}
+
+/// How well the current card was known.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Grade {
+    Again,
+    Hard,
+    Good,
+    Easy,
+}
+
+/// Number of cards given each grade.
+#[derive(Serialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Grades {
+    pub again: u32,
+    pub hard: u32,
+    pub good: u32,
+    pub easy: u32,
+}
+
+impl Grades {
+    fn record(&mut self, grade: Grade) {
+        let count = match grade {
+            Grade::Again => &mut self.again,
+            Grade::Hard => &mut self.hard,
+            Grade::Good => &mut self.good,
+            Grade::Easy => &mut self.easy,
+        };
+        *count += 1;
+    }
+}
+
+/// The study session since the app started.
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Session {
+    /// Unix time in milliseconds
+    pub started_at: u64,
+    pub cards_seen: u32,
+    pub grades: Grades,
+    /// Cards graded in a row without an "again"
+    pub streak: u32,
+    /// The cards the main window is cycling through
+    pub deck: Vec<String>,
+    /// Cards last graded good or easy; the rest of the deck is due
+    pub learned: BTreeSet<String>,
+}
+
+impl Session {
+    fn record(&mut self, card: &str, grade: Grade) {
+        self.grades.record(grade);
+        match grade {
+            Grade::Again => self.streak = 0,
+            _ => self.streak += 1,
+        }
+        match grade {
+            Grade::Again | Grade::Hard => self.learned.remove(card),
+            Grade::Good | Grade::Easy => self.learned.insert(card.to_string()),
+        };
+    }
+
+    /// Cards in the deck still to be reviewed.
+    pub fn due(&self) -> usize {
+        self.deck.iter().filter(|card| !self.learned.contains(*card)).count()
+    }
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AppState {
+    pub click_through: bool,
+    /// Cards and position stay put while paused
+    pub paused: bool,
+    /// Unix time in milliseconds at which the temporary icon reveal ends
+    pub reveal_until: Option<u64>,
+    pub session: Session,
+    pub current_card: Option<CurrentCard>,
+    pub provider_status: ProviderStatus,
+    pub window_mode: WindowMode,
+}
+
+impl Default for AppState {
+    fn default() -> Self {
+        AppState {
+            click_through: false,
+            paused: false,
+            reveal_until: None,
+            session: Session {
+                started_at: now_millis(),
+                cards_seen: 0,
+                grades: Grades::default(),
+                streak: 0,
+                deck: Vec::new(),
+                learned: BTreeSet::new(),
+            },
+            current_card: None,
+            provider_status: ProviderStatus::Unknown,
+            window_mode: WindowMode::Visible,
+        }
+    }
+}
+
+/// Current Unix time in milliseconds.
+pub fn now_millis() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64
+}
+
+/// Holds the state, managed as Tauri state.
+#[derive(Default)]
+pub struct StateStore {
+    state: Mutex<AppState>,
+}
+
+impl StateStore {
+    pub fn get(&self) -> AppState {
+        crate::lock(&self.state).clone()
+    }
+
+    /// Applies `f` and broadcasts `state-changed` if anything changed, also
+    /// to the tray. The lock is held across the emit so windows see changes
+    /// in order.
+    pub fn update<T>(&self, app_handle: &tauri::AppHandle, f: impl FnOnce(&mut AppState) -> T) -> T {
+        let mut state = crate::lock(&self.state);
+        let previous = state.clone();
+        let result = f(&mut state);
+        if *state != previous {
+            let _ = app_handle.emit_all("state-changed", &*state);
+            crate::tray::update(app_handle, &state);
+        }
+        result
+    }
+}
+
+#[tauri::command]
+pub fn get_app_state(store: tauri::State<StateStore>) -> AppState {
+    store.get()
+}
+
+/// Records the card now shown on the main window and gives it the full
+/// display time before the next one.
+#[tauri::command]
+pub fn set_current_card(app_handle: tauri::AppHandle, store: tauri::State<StateStore>, timers: tauri::State<Timers>, card: CurrentCard) {
+    let (new_card, paused) = store.update(&app_handle, |state| {
+        // A translation arriving for the card on screen is not a new card
+        let new_card = !state.current_card.as_ref().is_some_and(|current| current.index == card.index && current.text == card.text);
+        if new_card {
+            state.session.cards_seen += 1;
+        }
+        state.current_card = Some(card);
+        (new_card, state.paused)
+    });
+    if new_card && !paused {
+        schedule_next_card(&app_handle, &timers);
+    }
+}
+
+/// (Re)starts the countdown to the next card. The main window shows it when
+/// it gets `next-card`.
+fn schedule_next_card(app_handle: &tauri::AppHandle, timers: &Timers) {
+    let main_window = app_handle.clone();
+    timers.repeat(CARD_TIMER, CARD_DISPLAY_TIME, move || {
+        let _ = main_window.emit_to("main", "next-card", ());
+    });
+}
+
+/// Records a grade for the current card and moves on to the next one.
+pub fn grade_card(app_handle: &tauri::AppHandle, grade: Grade) {
+    let graded = app_handle.state::<StateStore>().update(app_handle, |state| {
+        let Some(card) = &state.current_card else {
+            return false;
+        };
+        state.session.record(&card.text, grade);
+        true
+    });
+    if graded {
+        println!("📝 Graded card: {:?}", grade);
+        let _ = app_handle.emit_to("main", "next-card", ());
+    }
+}
+
+/// Stops or restarts card cycling and position rotation.
+pub fn set_paused(app_handle: &tauri::AppHandle, paused: bool) {
+    app_handle.state::<StateStore>().update(app_handle, |state| state.paused = paused);
+    let timers = app_handle.state::<Timers>();
+    if paused {
+        timers.cancel(CARD_TIMER);
+        println!("⏸️ Paused card rotation");
+    } else {
+        schedule_next_card(app_handle, &timers);
+        println!("▶️ Resumed card rotation");
+    }
+}
+
+pub fn toggle_paused(app_handle: &tauri::AppHandle) {
+    let paused = app_handle.state::<StateStore>().get().paused;
+    set_paused(app_handle, !paused);
+}
+
+/// Lets the frontend report provider activity, such as a generation run.
+#[tauri::command]
+pub fn set_provider_status(app_handle: tauri::AppHandle, store: tauri::State<StateStore>, status: ProviderStatus) {
+    store.update(&app_handle, |state| state.provider_status = status);
+}
+
+/// Records the cards the main window cycles through, which the due count
+/// is taken from.
+#[tauri::command]
+pub fn set_deck(app_handle: tauri::AppHandle, store: tauri::State<StateStore>, cards: Vec<String>) {
+    store.update(&app_handle, |state| state.session.deck = cards);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn state_serializes_camel_case() {
+        let state = AppState {
+            current_card: Some(CurrentCard {
+                index: 2,
+                text: "bonjour".to_string(),
+                translation: None,
+            }),
+            ..AppState::default()
+        };
+        let value = serde_json::to_value(&state).unwrap();
+        assert_eq!(value["clickThrough"], false);
+        assert_eq!(value["revealUntil"], serde_json::Value::Null);
+        assert_eq!(value["currentCard"]["text"], "bonjour");
+        assert_eq!(value["providerStatus"], "unknown");
+        assert_eq!(value["windowMode"], "visible");
+        assert_eq!(value["session"]["cardsSeen"], 0);
+        assert_eq!(value["session"]["grades"]["good"], 0);
+    }
+
+    #[test]
+    fn grades_are_counted_separately() {
+        let mut grades = Grades::default();
+        grades.record(Grade::Good);
+        grades.record(Grade::Good);
+        grades.record(Grade::Again);
+        assert_eq!(grades, Grades { again: 1, hard: 0, good: 2, easy: 0 });
+    }
+
+    #[test]
+    fn grading_moves_cards_in_and_out_of_the_due_count() {
+        let mut session = AppState::default().session;
+        session.deck = vec!["chien".to_string(), "chat".to_string(), "eau".to_string()];
+        assert_eq!(session.due(), 3);
+
+        session.record("chien", Grade::Good);
+        session.record("chat", Grade::Easy);
+        assert_eq!((session.due(), session.streak), (1, 2));
+
+        session.record("chien", Grade::Again);
+        assert_eq!((session.due(), session.streak), (2, 0));
+
+        session.record("eau", Grade::Hard);
+        assert_eq!((session.due(), session.streak), (2, 1));
+    }
+
+    #[test]
+    fn poisoned_lock_is_recovered() {
+        let store = std::sync::Arc::new(StateStore::default());
+        let poisoner = std::sync::Arc::clone(&store);
+        let _ = std::thread::spawn(move || {
+            let mut state = crate::lock(&poisoner.state);
+            state.click_through = true;
+            panic!("poison the lock");
+        })
+        .join();
+
+        assert!(store.state.is_poisoned());
+        assert!(store.get().click_through);
+    }
+}
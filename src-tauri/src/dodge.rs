@@ -0,0 +1,330 @@
+//! Dodge mode: keeps the card off the focused window and, while the card is
+//! click-through, out from under the mouse pointer. When the card is in the
+//! way for a moment it moves to the nearest grid slot that is clear.
+//!
+//! Only X11 exposes the pointer and the active window to other clients, so
+//! this does nothing elsewhere.
+#![cfg_attr(not(target_os = "linux"), allow(dead_code))]
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::layout::{Point, Rect, Size};
+
+#[cfg(target_os = "linux")]
+mod x11;
+
+/// How often the pointer and active window are sampled.
+#[cfg(target_os = "linux")]
+const TICK_INTERVAL: Duration = Duration::from_millis(150);
+#[cfg(target_os = "linux")]
+const DODGE_TIMER: &str = "dodge";
+/// How often panels are re-read. Reading them opens an X connection and
+/// walks every client window, far too much for each tick.
+#[cfg(target_os = "linux")]
+const WORK_AREA_REFRESH: Duration = Duration::from_secs(5);
+/// How long the card has to be in the way before it moves.
+const DEBOUNCE: Duration = Duration::from_millis(500);
+/// Minimum time between two moves.
+const COOLDOWN: Duration = Duration::from_millis(1500);
+/// A new slot must be this many times the margin clear of obstacles, so
+/// small pointer movements do not send the card straight back.
+const CLEARANCE_FACTOR: f64 = 3.0;
+
+/// Whether the card sits where dodge mode moved it, managed as Tauri state.
+/// Anchored cards are normally put back on their anchor whenever they
+/// resize; a dodged card is left where it is until it is placed on purpose
+/// again, or it would jump back onto what it just avoided.
+#[derive(Default)]
+pub struct Dodged(AtomicBool);
+
+pub fn is_dodged(app_handle: &tauri::AppHandle) -> bool {
+    use tauri::Manager;
+    app_handle.state::<Dodged>().0.load(Ordering::Relaxed)
+}
+
+/// Called whenever the card is placed deliberately, by the user, a setting
+/// or position rotation.
+pub fn clear(app_handle: &tauri::AppHandle) {
+    use tauri::Manager;
+    app_handle.state::<Dodged>().0.store(false, Ordering::Relaxed);
+}
+
+/// What the card should stay away from, in the same coordinates as the card.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Obstacles {
+    pub pointer: Option<Point>,
+    pub active_window: Option<Rect>,
+}
+
+impl Obstacles {
+    /// Whether a card at `card`, grown by `margin`, covers an obstacle.
+    pub fn blocked_by(&self, card: Rect, margin: f64) -> bool {
+        let card = card.inflate(margin);
+        self.pointer.is_some_and(|pointer| card.contains(pointer))
+            || self.active_window.is_some_and(|window| card.intersection(&window).is_some())
+    }
+}
+
+/// The clear slot closest to `from`, or `None` when every slot is in the way.
+pub fn free_slot(slots: &[Point], window: Size, from: Point, obstacles: &Obstacles, margin: f64) -> Option<Point> {
+    let clearance = margin.max(1.0) * CLEARANCE_FACTOR;
+    let distance = |slot: &Point| (slot.x - from.x).hypot(slot.y - from.y);
+    slots
+        .iter()
+        .filter(|slot| !obstacles.blocked_by(Rect::new(slot.x, slot.y, window.width, window.height), clearance))
+        .min_by(|a, b| distance(a).total_cmp(&distance(b)))
+        .copied()
+}
+
+/// Decides when the card has been in the way long enough to move, and keeps
+/// it from moving again straight after.
+#[derive(Debug, Default)]
+pub struct Debounce {
+    blocked_since: Option<Instant>,
+    last_move: Option<Instant>,
+}
+
+impl Debounce {
+    pub fn should_move(&mut self, blocked: bool, now: Instant) -> bool {
+        if !blocked {
+            self.blocked_since = None;
+            return false;
+        }
+        let since = *self.blocked_since.get_or_insert(now);
+        let cooling_down = self.last_move.is_some_and(|last| now.duration_since(last) < COOLDOWN);
+        !cooling_down && now.duration_since(since) >= DEBOUNCE
+    }
+
+    pub fn moved(&mut self, now: Instant) {
+        self.last_move = Some(now);
+        self.blocked_since = None;
+    }
+}
+
+/// The work areas as last read, refreshed every `WORK_AREA_REFRESH`.
+#[cfg(target_os = "linux")]
+struct CachedWorkAreas {
+    areas: crate::work_area::WorkAreas,
+    read_at: Instant,
+}
+
+#[cfg(target_os = "linux")]
+impl CachedWorkAreas {
+    fn read() -> Self {
+        CachedWorkAreas {
+            areas: crate::work_area::WorkAreas::current(),
+            read_at: Instant::now(),
+        }
+    }
+
+    fn get(&mut self) -> &crate::work_area::WorkAreas {
+        if self.read_at.elapsed() >= WORK_AREA_REFRESH {
+            *self = CachedWorkAreas::read();
+        }
+        &self.areas
+    }
+}
+
+/// What the dodge timer keeps between ticks. It is dropped with the timer,
+/// which also closes the X connection.
+#[cfg(target_os = "linux")]
+#[derive(Default)]
+struct Watcher {
+    probe: Option<x11::Probe>,
+    debounce: Debounce,
+    work_areas: Option<CachedWorkAreas>,
+    reported_unavailable: bool,
+}
+
+#[cfg(target_os = "linux")]
+impl Watcher {
+    fn tick(&mut self, app_handle: &tauri::AppHandle) {
+        use tauri::Manager;
+
+        let settings = app_handle.state::<crate::settings::SettingsStore>().get();
+        if !settings.dodge_mode {
+            // Turned off while this tick was due; `schedule` cancels the timer
+            return;
+        }
+
+        if self.probe.is_none() {
+            self.probe = x11::Probe::open();
+        }
+        let Some(probe) = &self.probe else {
+            if !self.reported_unavailable {
+                println!("⚠️ Dodge mode needs an X11 session, it is inactive");
+                self.reported_unavailable = true;
+            }
+            return;
+        };
+
+        let work_areas = self.work_areas.get_or_insert_with(CachedWorkAreas::read).get();
+        dodge(app_handle, &settings, probe, work_areas, &mut self.debounce);
+    }
+}
+
+/// Arms or cancels the timer that watches the pointer and the active window
+/// to match `settings`. A running timer keeps its X connection and debounce
+/// state across settings changes.
+#[cfg(target_os = "linux")]
+pub fn schedule(app_handle: &tauri::AppHandle, settings: &crate::settings::Settings) {
+    use tauri::Manager;
+
+    use crate::timers::Timers;
+
+    let timers = app_handle.state::<Timers>();
+    if !settings.dodge_mode {
+        timers.cancel(DODGE_TIMER);
+        clear(app_handle);
+        return;
+    }
+    if timers.period(DODGE_TIMER).is_some() {
+        return;
+    }
+
+    let app_handle = app_handle.clone();
+    let mut watcher = Watcher::default();
+    timers.repeat(DODGE_TIMER, TICK_INTERVAL, move || watcher.tick(&app_handle));
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn schedule(_app_handle: &tauri::AppHandle, _settings: &crate::settings::Settings) {}
+
+/// Moves the card out of the way if it has been covering something.
+#[cfg(target_os = "linux")]
+fn dodge(
+    app_handle: &tauri::AppHandle,
+    settings: &crate::settings::Settings,
+    probe: &x11::Probe,
+    work_areas: &crate::work_area::WorkAreas,
+    debounce: &mut Debounce,
+) {
+    use tauri::{Manager, PhysicalPosition};
+
+    use crate::layout;
+    use crate::window_motion::{Motion, WindowMotion};
+
+    if crate::snapping::is_dragging(app_handle) {
+        return;
+    }
+    let Some(main_window) = app_handle.get_window("main") else {
+        return;
+    };
+    let (Ok(Some(monitor)), Some(size), Ok(position)) = (
+        main_window.current_monitor(),
+        crate::logical_window_size(&main_window),
+        main_window.outer_position(),
+    ) else {
+        return;
+    };
+
+    let monitor = crate::layout_monitor(&monitor, work_areas);
+
+    // Everything is compared in the monitor's logical pixels
+    let to_local = |x: f64, y: f64| Point::new((x - monitor.bounds.x) / monitor.scale_factor, (y - monitor.bounds.y) / monitor.scale_factor);
+    let origin = to_local(f64::from(position.x), f64::from(position.y));
+    let card = Rect::new(origin.x, origin.y, size.width, size.height);
+
+    // A card that takes clicks is meant to be pointed at
+    let pointer = if settings.click_through { probe.pointer() } else { None };
+    let obstacles = Obstacles {
+        pointer: pointer.map(|(x, y)| to_local(x, y)),
+        active_window: probe.active_window().map(|window| {
+            let origin = to_local(window.x, window.y);
+            Rect::new(origin.x, origin.y, window.width / monitor.scale_factor, window.height / monitor.scale_factor)
+        }),
+    };
+
+    let now = Instant::now();
+    if !debounce.should_move(obstacles.blocked_by(card, settings.dodge_margin), now) {
+        return;
+    }
+
+    let area = monitor.logical_area();
+    let grid = layout::grid_dimensions(area.size(), settings.auto_detect_grid, settings.manual_grid_cols, settings.manual_grid_rows);
+    let slots = layout::grid_positions(area, size, grid);
+    let Some(target) = free_slot(&slots, size, origin, &obstacles, settings.dodge_margin) else {
+        return;
+    };
+
+    debounce.moved(now);
+    println!("🏃 Dodging to ({}, {})", target.x, target.y);
+    let target = monitor.to_global(target);
+    let target = PhysicalPosition::new(target.x.round() as i32, target.y.round() as i32);
+    match app_handle.state::<WindowMotion>().move_to(&main_window, target, Motion::from_settings(settings)) {
+        Ok(()) => app_handle.state::<Dodged>().0.store(true, Ordering::Relaxed),
+        Err(e) => println!("⚠️ Failed to move card out of the way: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CARD: Rect = Rect { x: 100.0, y: 100.0, width: 400.0, height: 300.0 };
+
+    #[test]
+    fn pointer_near_the_card_blocks_it() {
+        let obstacles = Obstacles {
+            pointer: Some(Point::new(90.0, 150.0)),
+            active_window: None,
+        };
+        assert!(obstacles.blocked_by(CARD, 16.0));
+        assert!(!obstacles.blocked_by(CARD, 0.0));
+    }
+
+    #[test]
+    fn overlapping_active_window_blocks_the_card() {
+        let beside = Obstacles {
+            pointer: None,
+            active_window: Some(Rect::new(520.0, 0.0, 800.0, 600.0)),
+        };
+        assert!(!beside.blocked_by(CARD, 16.0));
+        assert!(beside.blocked_by(CARD, 24.0));
+        assert!(!Obstacles::default().blocked_by(CARD, 100.0));
+    }
+
+    #[test]
+    fn moves_to_the_nearest_clear_slot() {
+        let slots = [Point::new(100.0, 100.0), Point::new(600.0, 100.0), Point::new(1400.0, 100.0), Point::new(100.0, 700.0)];
+        let obstacles = Obstacles {
+            pointer: Some(Point::new(200.0, 200.0)),
+            active_window: Some(Rect::new(500.0, 0.0, 600.0, 500.0)),
+        };
+        let size = Size::new(400.0, 300.0);
+        assert_eq!(free_slot(&slots, size, Point::new(100.0, 100.0), &obstacles, 16.0), Some(Point::new(100.0, 700.0)));
+    }
+
+    #[test]
+    fn stays_put_when_nothing_is_clear() {
+        let everything = Obstacles {
+            pointer: None,
+            active_window: Some(Rect::new(0.0, 0.0, 1920.0, 1080.0)),
+        };
+        let slots = [Point::new(100.0, 100.0), Point::new(1400.0, 700.0)];
+        assert_eq!(free_slot(&slots, Size::new(400.0, 300.0), Point::new(0.0, 0.0), &everything, 16.0), None);
+    }
+
+    #[test]
+    fn waits_before_moving_and_cools_down_after() {
+        let start = Instant::now();
+        let mut debounce = Debounce::default();
+        assert!(!debounce.should_move(true, start));
+        assert!(!debounce.should_move(true, start + DEBOUNCE / 2));
+        assert!(debounce.should_move(true, start + DEBOUNCE));
+
+        debounce.moved(start + DEBOUNCE);
+        assert!(!debounce.should_move(true, start + DEBOUNCE + COOLDOWN / 2));
+        assert!(debounce.should_move(true, start + DEBOUNCE + COOLDOWN));
+    }
+
+    #[test]
+    fn brief_overlaps_do_not_count() {
+        let start = Instant::now();
+        let mut debounce = Debounce::default();
+        assert!(!debounce.should_move(true, start));
+        assert!(!debounce.should_move(false, start + DEBOUNCE / 2));
+        assert!(!debounce.should_move(true, start + DEBOUNCE));
+    }
+}
@@ -0,0 +1,126 @@
+//! Samples the pointer position and the active window from the X server.
+//! The connection stays open while dodge mode is on, since it is polled
+//! several times a second.
+
+use std::mem::MaybeUninit;
+use std::os::raw::{c_int, c_uint, c_ulong};
+use std::ptr;
+
+use x11_dl::xlib::{self, Display, Xlib};
+
+use crate::layout::Rect;
+use crate::work_area::x11::{cardinals, trap_errors};
+
+/// An open X display.
+pub struct Probe {
+    xlib: Xlib,
+    display: *mut Display,
+    root: c_ulong,
+}
+
+impl Probe {
+    /// Connects to the X server, or `None` when there is no X display.
+    pub fn open() -> Option<Probe> {
+        let xlib = Xlib::open().ok()?;
+        // SAFETY: the display is checked for null and closed in `drop`.
+        unsafe {
+            let display = (xlib.XOpenDisplay)(ptr::null());
+            if display.is_null() {
+                return None;
+            }
+            let root = (xlib.XDefaultRootWindow)(display);
+            Some(Probe { xlib, display, root })
+        }
+    }
+
+    /// The pointer position in root window (global physical) pixels.
+    pub fn pointer(&self) -> Option<(f64, f64)> {
+        let mut root_return: c_ulong = 0;
+        let mut child: c_ulong = 0;
+        let (mut root_x, mut root_y, mut window_x, mut window_y): (c_int, c_int, c_int, c_int) = (0, 0, 0, 0);
+        let mut mask: c_uint = 0;
+
+        // SAFETY: the display is open and all out-pointers are valid locals.
+        let on_screen = unsafe {
+            (self.xlib.XQueryPointer)(
+                self.display,
+                self.root,
+                &mut root_return,
+                &mut child,
+                &mut root_x,
+                &mut root_y,
+                &mut window_x,
+                &mut window_y,
+                &mut mask,
+            )
+        };
+        (on_screen != xlib::False).then_some((f64::from(root_x), f64::from(root_y)))
+    }
+
+    /// The frame of the focused window in root window pixels. Our own
+    /// windows and windows that are not shown are ignored.
+    pub fn active_window(&self) -> Option<Rect> {
+        // SAFETY: the display is open; `cardinals` checks the property type.
+        unsafe {
+            let window = *cardinals(&self.xlib, self.display, self.root, "_NET_ACTIVE_WINDOW", xlib::XA_WINDOW)?.first()? as c_ulong;
+            if window == 0 {
+                return None;
+            }
+            // The window may close while it is being measured
+            trap_errors(&self.xlib, self.display, || self.frame(window)).flatten()
+        }
+    }
+
+    /// The frame of another client's `window`, unless it is ours or hidden.
+    ///
+    /// # Safety
+    /// Requests about `window` fail once it is destroyed, so this has to run
+    /// inside `trap_errors`.
+    unsafe fn frame(&self, window: c_ulong) -> Option<Rect> {
+        let pid = cardinals(&self.xlib, self.display, window, "_NET_WM_PID", xlib::XA_CARDINAL).and_then(|values| values.first().copied());
+        if pid == Some(i64::from(std::process::id())) {
+            return None;
+        }
+
+        let mut attributes = MaybeUninit::<xlib::XWindowAttributes>::zeroed();
+        if (self.xlib.XGetWindowAttributes)(self.display, window, attributes.as_mut_ptr()) == 0 {
+            return None;
+        }
+        let attributes = attributes.assume_init();
+        if attributes.map_state != xlib::IsViewable {
+            return None;
+        }
+
+        let (mut x, mut y): (c_int, c_int) = (0, 0);
+        let mut child: c_ulong = 0;
+        if (self.xlib.XTranslateCoordinates)(self.display, window, self.root, 0, 0, &mut x, &mut y, &mut child) == 0 {
+            return None;
+        }
+
+        // Include the window manager's decorations: left, right, top, bottom
+        let frame = cardinals(&self.xlib, self.display, window, "_NET_FRAME_EXTENTS", xlib::XA_CARDINAL)
+            .filter(|values| values.len() >= 4)
+            .map(|values| [values[0], values[1], values[2], values[3]].map(|value| value as f64))
+            .unwrap_or_default();
+        Some(Rect::new(
+            f64::from(x) - frame[0],
+            f64::from(y) - frame[2],
+            f64::from(attributes.width) + frame[0] + frame[1],
+            f64::from(attributes.height) + frame[2] + frame[3],
+        ))
+    }
+}
+
+// SAFETY: Xlib connections may move between threads as long as only one
+// thread uses them at a time; a probe is owned by the dodge timer, whose
+// ticks never overlap.
+unsafe impl Send for Probe {}
+
+impl Drop for Probe {
+    fn drop(&mut self) {
+        // SAFETY: the display was opened in `open` and is closed only here.
+        unsafe {
+            (self.xlib.XCloseDisplay)(self.display);
+        }
+    }
+}
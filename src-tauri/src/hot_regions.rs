@@ -0,0 +1,140 @@
+//! Parts of the card that stay clickable in click-through mode, such as the
+//! icons and the drag handle. The frontend reports their rectangles, and on
+//! Linux the window's input shape is cut down to them so clicks everywhere
+//! else reach the windows underneath.
+//!
+//! macOS can only ignore mouse events for the whole window, so regions have
+//! no effect there and the temporary icon shortcut is still needed.
+
+use std::sync::Mutex;
+use tauri::Manager;
+
+use crate::layout::{Rect, Size};
+
+/// The registered regions, in logical pixels relative to the window content.
+#[derive(Default)]
+pub struct HotRegions {
+    regions: Mutex<Vec<Rect>>,
+}
+
+/// Whole-pixel rectangles (x, y, width, height) for the input shape: each
+/// region grown to whole pixels and clipped to the window. Regions outside
+/// the window are dropped.
+pub fn shape_rects(regions: &[Rect], window: Size) -> Vec<(i32, i32, i32, i32)> {
+    let bounds = Rect::new(0.0, 0.0, window.width, window.height);
+    regions
+        .iter()
+        .filter_map(|region| region.intersection(&bounds))
+        .map(|region| {
+            let (left, top) = (region.x.floor(), region.y.floor());
+            let (right, bottom) = (region.right().ceil(), region.bottom().ceil());
+            (left as i32, top as i32, (right - left) as i32, (bottom - top) as i32)
+        })
+        .collect()
+}
+
+/// Replaces the clickable regions of the main window.
+#[tauri::command]
+pub fn set_hot_regions(app_handle: tauri::AppHandle, hot_regions: tauri::State<HotRegions>, regions: Vec<Rect>) -> Result<(), String> {
+    if let Some(region) = regions.iter().find(|r| ![r.x, r.y, r.width, r.height].iter().all(|v| v.is_finite())) {
+        return Err(format!("Hot region must have finite coordinates, got {:?}", region));
+    }
+
+    let changed = {
+        let mut current = crate::lock(&hot_regions.regions);
+        let changed = *current != regions;
+        *current = regions;
+        changed
+    };
+    if changed {
+        refresh(&app_handle);
+    }
+    Ok(())
+}
+
+/// Re-applies the input shape, e.g. after the card resized and regions
+/// outside the old size have to be clipped differently.
+pub fn refresh(app_handle: &tauri::AppHandle) {
+    let state = app_handle.state::<crate::app_state::StateStore>().get();
+    // While icons are revealed the whole window takes input until the reveal ends
+    if state.click_through && state.reveal_until.is_none() {
+        if let Err(e) = apply(app_handle, true) {
+            println!("⚠️ Failed to update hot regions: {}", e);
+        }
+    }
+}
+
+/// Restricts the main window's input to the hot regions, or accepts input
+/// everywhere again when `click_through` is off.
+#[cfg(target_os = "linux")]
+pub fn apply(app_handle: &tauri::AppHandle, click_through: bool) -> Result<(), String> {
+    use gtk::cairo::{RectangleInt, Region};
+    use gtk::prelude::*;
+
+    let main_window = app_handle.get_window("main").ok_or("Could not find main window")?;
+    let rects = if click_through {
+        let size = main_window.inner_size().map_err(|e| e.to_string())?;
+        let scale_factor = main_window.scale_factor().map_err(|e| e.to_string())?;
+        let window = Size::new(f64::from(size.width) / scale_factor, f64::from(size.height) / scale_factor);
+        let regions = crate::lock(&app_handle.state::<HotRegions>().regions).clone();
+        Some(shape_rects(&regions, window))
+    } else {
+        None
+    };
+
+    // GTK may only be touched from the main thread
+    let window = main_window.clone();
+    main_window
+        .run_on_main_thread(move || {
+            let Ok(gtk_window) = window.gtk_window() else {
+                return;
+            };
+            match rects {
+                Some(rects) => {
+                    // An empty shape is ignored by some window managers, so an
+                    // empty region list keeps a single pixel, like tao does
+                    let mut rects: Vec<RectangleInt> = rects
+                        .into_iter()
+                        .map(|(x, y, width, height)| RectangleInt { x, y, width, height })
+                        .collect();
+                    if rects.is_empty() {
+                        rects.push(RectangleInt { x: 0, y: 0, width: 1, height: 1 });
+                    }
+                    if let Some(gdk_window) = gtk_window.window() {
+                        gdk_window.input_shape_combine_region(&Region::create_rectangles(&rects), 0, 0);
+                    }
+                }
+                None => gtk_window.input_shape_combine_region(None),
+            }
+        })
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn apply(_app_handle: &tauri::AppHandle, _click_through: bool) -> Result<(), String> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WINDOW: Size = Size { width: 400.0, height: 300.0 };
+
+    #[test]
+    fn regions_are_grown_to_whole_pixels() {
+        let regions = [Rect::new(10.4, 20.6, 24.2, 24.0)];
+        assert_eq!(shape_rects(&regions, WINDOW), vec![(10, 20, 25, 25)]);
+    }
+
+    #[test]
+    fn regions_are_clipped_to_the_window() {
+        let regions = [Rect::new(380.0, 290.0, 50.0, 50.0), Rect::new(500.0, 0.0, 10.0, 10.0)];
+        assert_eq!(shape_rects(&regions, WINDOW), vec![(380, 290, 20, 10)]);
+    }
+
+    #[test]
+    fn no_regions_means_no_rectangles() {
+        assert!(shape_rects(&[], WINDOW).is_empty());
+    }
+}
@@ -0,0 +1,793 @@
+//! Window placement math, kept free of Tauri types so it can be tested.
+//!
+//! The plain functions work in a single coordinate space: rectangles, window
+//! sizes and the returned positions must use the same units. `Monitor` maps
+//! between a monitor's own logical pixels, where margins and offsets are
+//! defined, and the global physical pixels windows are positioned in.
+
+use serde::{Deserialize, Serialize};
+
+/// Margin kept between grid slots and the edge of the placement area.
+pub const GRID_MARGIN: f64 = 50.0;
+/// Margin kept between random positions and the edge of the placement area.
+pub const RANDOM_MARGIN: f64 = 100.0;
+/// Space between the main card and the settings window.
+pub const SETTINGS_GAP: f64 = 20.0;
+/// Extra room required before the settings window is placed beside the card.
+pub const SETTINGS_BUFFER: f64 = 50.0;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl Point {
+    pub fn new(x: f64, y: f64) -> Self {
+        Point { x, y }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub struct Size {
+    pub width: f64,
+    pub height: f64,
+}
+
+impl Size {
+    pub fn new(width: f64, height: f64) -> Self {
+        Size { width, height }
+    }
+
+    pub fn aspect_ratio(&self) -> f64 {
+        if self.height > 0.0 {
+            self.width / self.height
+        } else {
+            1.0
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Rect {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl Rect {
+    pub fn new(x: f64, y: f64, width: f64, height: f64) -> Self {
+        Rect { x, y, width, height }
+    }
+
+    pub fn size(&self) -> Size {
+        Size::new(self.width, self.height)
+    }
+
+    pub fn right(&self) -> f64 {
+        self.x + self.width
+    }
+
+    pub fn bottom(&self) -> f64 {
+        self.y + self.height
+    }
+
+    pub fn center(&self) -> Point {
+        Point::new(self.x + self.width / 2.0, self.y + self.height / 2.0)
+    }
+
+    /// The rectangle grown by `margin` on every side.
+    pub fn inflate(&self, margin: f64) -> Rect {
+        Rect::new(self.x - margin, self.y - margin, self.width + 2.0 * margin, self.height + 2.0 * margin)
+    }
+
+    /// Half-open containment: the right and bottom edges belong to the
+    /// neighbouring monitor.
+    pub fn contains(&self, point: Point) -> bool {
+        point.x >= self.x && point.x < self.right() && point.y >= self.y && point.y < self.bottom()
+    }
+
+    /// The overlap of two rectangles, or `None` if they do not overlap.
+    pub fn intersection(&self, other: &Rect) -> Option<Rect> {
+        let x = self.x.max(other.x);
+        let y = self.y.max(other.y);
+        let right = self.right().min(other.right());
+        let bottom = self.bottom().min(other.bottom());
+        if right > x && bottom > y {
+            Some(Rect::new(x, y, right - x, bottom - y))
+        } else {
+            None
+        }
+    }
+}
+
+/// A monitor's bounds and work area in global physical pixels, and its
+/// scale factor. The work area is the part not covered by panels, docks and
+/// taskbars; windows are placed within it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Monitor {
+    pub bounds: Rect,
+    pub work_area: Rect,
+    pub scale_factor: f64,
+}
+
+impl Monitor {
+    /// Scale factors that are not positive and finite are treated as 1.0.
+    /// The work area starts out as the whole monitor.
+    pub fn new(bounds: Rect, scale_factor: f64) -> Self {
+        let scale_factor = if scale_factor.is_finite() && scale_factor > 0.0 { scale_factor } else { 1.0 };
+        Monitor {
+            bounds,
+            work_area: bounds,
+            scale_factor,
+        }
+    }
+
+    /// Restricts placement to `work_area`, ignoring work areas that do not
+    /// overlap the monitor at all.
+    pub fn with_work_area(mut self, work_area: Rect) -> Self {
+        self.work_area = self.bounds.intersection(&work_area).unwrap_or(self.bounds);
+        self
+    }
+
+    /// The work area in the monitor's own logical pixels, relative to the
+    /// monitor's top-left corner.
+    pub fn logical_area(&self) -> Rect {
+        Rect::new(
+            (self.work_area.x - self.bounds.x) / self.scale_factor,
+            (self.work_area.y - self.bounds.y) / self.scale_factor,
+            self.work_area.width / self.scale_factor,
+            self.work_area.height / self.scale_factor,
+        )
+    }
+
+    pub fn to_physical(self, size: Size) -> Size {
+        Size::new(size.width * self.scale_factor, size.height * self.scale_factor)
+    }
+
+    /// Maps a point in `logical_area` space to global physical pixels.
+    pub fn to_global(self, point: Point) -> Point {
+        Point::new(
+            self.bounds.x + point.x * self.scale_factor,
+            self.bounds.y + point.y * self.scale_factor,
+        )
+    }
+}
+
+/// Number of grid columns and rows; both are at least 1.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Grid {
+    cols: u32,
+    rows: u32,
+}
+
+impl Grid {
+    /// Returns `None` unless both dimensions are positive.
+    pub fn new(cols: i32, rows: i32) -> Option<Grid> {
+        if cols > 0 && rows > 0 {
+            Some(Grid {
+                cols: cols as u32,
+                rows: rows as u32,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Picks a grid that suits the aspect ratio of the placement area.
+    pub fn auto_detect(area: Size) -> Grid {
+        let aspect_ratio = area.aspect_ratio();
+        let (cols, rows) = if aspect_ratio > 2.0 {
+            (6, 3) // Ultra-wide
+        } else if aspect_ratio > 1.8 {
+            (5, 3) // Wide
+        } else if aspect_ratio > 1.5 {
+            (4, 3) // Standard
+        } else {
+            (3, 4) // Square-ish
+        };
+        Grid { cols, rows }
+    }
+
+    pub fn cols(&self) -> u32 {
+        self.cols
+    }
+
+    pub fn rows(&self) -> u32 {
+        self.rows
+    }
+
+    pub fn slot_count(&self) -> u32 {
+        self.cols * self.rows
+    }
+
+    /// Row and column of a slot, wrapping slots past the end of the grid.
+    pub fn cell(&self, slot: u32) -> (u32, u32) {
+        let slot = slot % self.slot_count();
+        (slot / self.cols, slot % self.cols)
+    }
+}
+
+/// The grid to use for `area`: auto-detected, or the manual override when
+/// it is valid. An empty manual grid falls back to auto-detection.
+pub fn grid_dimensions(area: Size, auto_detect: bool, manual_cols: i32, manual_rows: i32) -> Grid {
+    if auto_detect {
+        return Grid::auto_detect(area);
+    }
+    Grid::new(manual_cols, manual_rows).unwrap_or_else(|| Grid::auto_detect(area))
+}
+
+/// Position of a grid slot within `area`. Slots are spread evenly between
+/// the margins; a single column or row is centred.
+pub fn grid_slot_position(area: Rect, window: Size, grid: Grid, slot: u32) -> Point {
+    let (row, col) = grid.cell(slot);
+    let x = area.x + distribute(area.width, window.width, GRID_MARGIN, col, grid.cols());
+    let y = area.y + distribute(area.height, window.height, GRID_MARGIN, row, grid.rows());
+    Point::new(x, y)
+}
+
+/// Positions of every slot in the grid, in slot order.
+pub fn grid_positions(area: Rect, window: Size, grid: Grid) -> Vec<Point> {
+    (0..grid.slot_count())
+        .map(|slot| grid_slot_position(area, window, grid, slot))
+        .collect()
+}
+
+/// Offset of slot `index` out of `count` along one axis. When the window and
+/// margins do not fit, the margins shrink first and the window is centred
+/// once even that is not enough.
+fn distribute(extent: f64, window: f64, margin: f64, index: u32, count: u32) -> f64 {
+    let free = (extent - window).max(0.0);
+    let margin = margin.min(free / 2.0);
+    let available = free - 2.0 * margin;
+
+    if count <= 1 {
+        margin + available / 2.0
+    } else {
+        margin + index as f64 * available / (count - 1) as f64
+    }
+}
+
+/// A pseudo-random position inside `area`, derived from `seed`. The window
+/// stays fully inside the area; margins shrink when space is tight.
+pub fn random_position(area: Rect, window: Size, seed: u64) -> Point {
+    let x = area.x + random_offset(area.width, window.width, RANDOM_MARGIN, seed);
+    let y = area.y + random_offset(area.height, window.height, RANDOM_MARGIN, seed >> 32);
+    Point::new(x, y)
+}
+
+fn random_offset(extent: f64, window: f64, margin: f64, seed: u64) -> f64 {
+    let free = (extent - window).max(0.0);
+    let margin = margin.min(free / 2.0);
+    let span = (free - 2.0 * margin).floor() as u64;
+
+    if span == 0 {
+        free / 2.0
+    } else {
+        margin + (seed % (span + 1)) as f64
+    }
+}
+
+/// A point on the placement area that a window can be pinned to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Anchor {
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+/// Where a window lines up along one axis.
+enum Alignment {
+    Start,
+    Middle,
+    End,
+}
+
+impl Anchor {
+    pub const ALL: [Anchor; 9] = [
+        Anchor::TopLeft,
+        Anchor::Top,
+        Anchor::TopRight,
+        Anchor::Left,
+        Anchor::Center,
+        Anchor::Right,
+        Anchor::BottomLeft,
+        Anchor::Bottom,
+        Anchor::BottomRight,
+    ];
+
+    fn alignment(&self) -> (Alignment, Alignment) {
+        use Alignment::*;
+        match self {
+            Anchor::TopLeft => (Start, Start),
+            Anchor::Top => (Middle, Start),
+            Anchor::TopRight => (End, Start),
+            Anchor::Left => (Start, Middle),
+            Anchor::Center => (Middle, Middle),
+            Anchor::Right => (End, Middle),
+            Anchor::BottomLeft => (Start, End),
+            Anchor::Bottom => (Middle, End),
+            Anchor::BottomRight => (End, End),
+        }
+    }
+
+    fn from_alignment(horizontal: Alignment, vertical: Alignment) -> Anchor {
+        use Alignment::*;
+        match (horizontal, vertical) {
+            (Start, Start) => Anchor::TopLeft,
+            (Middle, Start) => Anchor::Top,
+            (End, Start) => Anchor::TopRight,
+            (Start, Middle) => Anchor::Left,
+            (Middle, Middle) => Anchor::Center,
+            (End, Middle) => Anchor::Right,
+            (Start, End) => Anchor::BottomLeft,
+            (Middle, End) => Anchor::Bottom,
+            (End, End) => Anchor::BottomRight,
+        }
+    }
+}
+
+/// How anchor offsets are measured.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OffsetUnit {
+    /// Logical pixels
+    #[default]
+    Px,
+    /// Percent of the placement area's width (x) or height (y)
+    Percent,
+}
+
+impl OffsetUnit {
+    /// Converts an offset along an axis of length `extent` to pixels.
+    pub fn to_pixels(self, value: f64, extent: f64) -> f64 {
+        match self {
+            OffsetUnit::Px => value,
+            OffsetUnit::Percent => value / 100.0 * extent,
+        }
+    }
+}
+
+/// Position of a window pinned to `anchor`. Offsets point inwards from the
+/// anchored edges (and right/down from a centred axis); the result is kept
+/// inside `area`.
+pub fn anchor_position(area: Rect, window: Size, anchor: Anchor, offset: Point) -> Point {
+    let (horizontal, vertical) = anchor.alignment();
+    let along = |alignment: Alignment, start: f64, extent: f64, window: f64, offset: f64| match alignment {
+        Alignment::Start => start + offset,
+        Alignment::Middle => start + (extent - window) / 2.0 + offset,
+        Alignment::End => start + extent - window - offset,
+    };
+    let position = Point::new(
+        along(horizontal, area.x, area.width, window.width, offset.x),
+        along(vertical, area.y, area.height, window.height, offset.y),
+    );
+    clamp_into(area, window, position)
+}
+
+/// The anchor and pixel offsets that reproduce `window`'s position in
+/// `area`, the inverse of `anchor_position`. The anchor is picked by which
+/// third of the area the window's centre falls in, so the card keeps its
+/// nearest edges when it or the area changes size.
+pub fn anchor_for(area: Rect, window: Rect) -> (Anchor, Point) {
+    let center = window.center();
+    let along = |center: f64, start: f64, extent: f64, position: f64, size: f64| {
+        if center < start + extent / 3.0 {
+            (Alignment::Start, position - start)
+        } else if center > start + extent * 2.0 / 3.0 {
+            (Alignment::End, start + extent - position - size)
+        } else {
+            (Alignment::Middle, position - start - (extent - size) / 2.0)
+        }
+    };
+    let (horizontal, offset_x) = along(center.x, area.x, area.width, window.x, window.width);
+    let (vertical, offset_y) = along(center.y, area.y, area.height, window.y, window.height);
+    (Anchor::from_alignment(horizontal, vertical), Point::new(offset_x, offset_y))
+}
+
+/// Top-left position that centres `window` in `area`.
+pub fn center_in(area: Rect, window: Size) -> Point {
+    Point::new(
+        area.x + (area.width - window.width) / 2.0,
+        area.y + (area.height - window.height) / 2.0,
+    )
+}
+
+/// Moves `window` at `position` the minimum distance needed to lie inside
+/// `area`. Windows larger than the area are pinned to its top-left corner.
+pub fn clamp_into(area: Rect, window: Size, position: Point) -> Point {
+    let max_x = (area.right() - window.width).max(area.x);
+    let max_y = (area.bottom() - window.height).max(area.y);
+    Point::new(position.x.max(area.x).min(max_x), position.y.max(area.y).min(max_y))
+}
+
+/// Index of the monitor containing `point`, if any.
+pub fn monitor_containing(monitors: &[Rect], point: Point) -> Option<usize> {
+    monitors.iter().position(|monitor| monitor.contains(point))
+}
+
+/// Index of the monitor a window is on, judged by its centre. Falls back to
+/// the first monitor when the centre is off-screen.
+pub fn monitor_for_window(monitors: &[Rect], window: Rect) -> Option<usize> {
+    monitor_containing(monitors, window.center()).or(if monitors.is_empty() { None } else { Some(0) })
+}
+
+/// Where to open the settings window so it does not cover the card.
+///
+/// `main` and the result are in global physical pixels; `settings` is the
+/// logical size of the settings window, scaled for whichever monitor it
+/// lands on. Tries right, left, below and above the card on its own
+/// monitor, then (if `allow_other_monitor`) the centre of another monitor
+/// with enough room, and finally a corner of the card's monitor. The result
+/// always lies inside the chosen monitor.
+pub fn settings_window_position(main: Rect, monitors: &[Monitor], settings: Size, allow_other_monitor: bool) -> Option<Point> {
+    let bounds: Vec<Rect> = monitors.iter().map(|monitor| monitor.bounds).collect();
+    let index = monitor_for_window(&bounds, main)?;
+    let screen = monitors[index].work_area;
+    let scale = monitors[index].scale_factor;
+
+    let size = monitors[index].to_physical(settings);
+    let gap = SETTINGS_GAP * scale;
+    let buffer = SETTINGS_BUFFER * scale;
+
+    let beside = |x: f64, y: f64| Some(clamp_into(screen, size, Point::new(x, y)));
+    let clamped_y = main.y.max(screen.y).min(screen.bottom() - size.height);
+    let clamped_x = main.x.max(screen.x).min(screen.right() - size.width);
+
+    // 1. Right of the card
+    if main.right() + size.width + gap + buffer <= screen.right() {
+        return beside(main.right() + gap, clamped_y);
+    }
+    // 2. Left of the card
+    if main.x - size.width - gap >= screen.x + buffer {
+        return beside(main.x - size.width - gap, clamped_y);
+    }
+    // 3. Below the card
+    if main.bottom() + size.height + gap <= screen.bottom() - buffer {
+        return beside(clamped_x, main.bottom() + gap);
+    }
+    // 4. Above the card
+    if main.y - size.height - gap >= screen.y + buffer {
+        return beside(clamped_x, main.y - size.height - gap);
+    }
+
+    // 5. Another monitor with comfortable room
+    if allow_other_monitor {
+        let other = monitors.iter().enumerate().find(|(i, monitor)| {
+            let area = monitor.logical_area();
+            *i != index && area.width >= settings.width + 100.0 && area.height >= settings.height + 100.0
+        });
+        if let Some((_, monitor)) = other {
+            return Some(monitor.to_global(center_in(monitor.logical_area(), settings)));
+        }
+    }
+
+    // 6. Offset from the corner of the card's monitor's work area
+    beside(screen.x + GRID_MARGIN * scale, screen.y + GRID_MARGIN * scale)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    const FULL_HD: Rect = Rect { x: 0.0, y: 0.0, width: 1920.0, height: 1080.0 };
+    const CARD: Size = Size { width: 400.0, height: 300.0 };
+    const SETTINGS: Size = Size { width: 400.0, height: 600.0 };
+
+    fn at_1x(bounds: Rect) -> Monitor {
+        Monitor::new(bounds, 1.0)
+    }
+
+    fn inside(area: Rect, window: Size, p: Point) -> bool {
+        const EPS: f64 = 1e-6;
+        p.x >= area.x - EPS
+            && p.y >= area.y - EPS
+            && p.x + window.width <= area.right() + EPS
+            && p.y + window.height <= area.bottom() + EPS
+    }
+
+    #[test]
+    fn grid_rejects_empty_dimensions() {
+        assert_eq!(Grid::new(0, 3), None);
+        assert_eq!(Grid::new(4, 0), None);
+        assert_eq!(Grid::new(-1, 3), None);
+        assert_eq!(Grid::new(4, 3).map(|g| g.slot_count()), Some(12));
+    }
+
+    #[test]
+    fn auto_detect_matches_aspect_ratio() {
+        assert_eq!(Grid::auto_detect(Size::new(3440.0, 1440.0)), Grid::new(6, 3).unwrap());
+        assert_eq!(Grid::auto_detect(Size::new(2560.0, 1080.0 * 1.3)), Grid::new(5, 3).unwrap());
+        assert_eq!(Grid::auto_detect(Size::new(1920.0, 1080.0)), Grid::new(4, 3).unwrap());
+        assert_eq!(Grid::auto_detect(Size::new(1280.0, 1024.0)), Grid::new(3, 4).unwrap());
+        assert_eq!(Grid::auto_detect(Size::new(1024.0, 0.0)), Grid::new(3, 4).unwrap());
+    }
+
+    #[test]
+    fn grid_dimensions_prefers_valid_manual_grid() {
+        let screen = FULL_HD.size();
+        assert_eq!(grid_dimensions(screen, false, 2, 5), Grid::new(2, 5).unwrap());
+        assert_eq!(grid_dimensions(screen, true, 2, 5), Grid::new(4, 3).unwrap());
+        assert_eq!(grid_dimensions(screen, false, 0, 5), Grid::new(4, 3).unwrap());
+    }
+
+    #[test]
+    fn grid_slots_span_the_margins() {
+        let grid = Grid::new(4, 3).unwrap();
+        let positions = grid_positions(FULL_HD, CARD, grid);
+        assert_eq!(positions.len(), 12);
+        assert_eq!(positions[0], Point::new(50.0, 50.0));
+        assert_eq!(positions[3], Point::new(1920.0 - 400.0 - 50.0, 50.0));
+        assert_eq!(positions[11], Point::new(1470.0, 1080.0 - 300.0 - 50.0));
+        // Slot 4 is the first slot of the middle row
+        assert_eq!(positions[4], Point::new(50.0, 390.0));
+    }
+
+    #[test]
+    fn grid_slots_are_offset_by_the_area_origin() {
+        let second_monitor = Rect::new(1920.0, -200.0, 1920.0, 1080.0);
+        let grid = Grid::new(4, 3).unwrap();
+        let p = grid_slot_position(second_monitor, CARD, grid, 0);
+        assert_eq!(p, Point::new(1970.0, -150.0));
+    }
+
+    #[test]
+    fn single_row_and_column_are_centred() {
+        let grid = Grid::new(1, 1).unwrap();
+        assert_eq!(grid_slot_position(FULL_HD, CARD, grid, 0), center_in(FULL_HD, CARD));
+    }
+
+    #[test]
+    fn grid_slot_wraps_past_the_end() {
+        let grid = Grid::new(3, 2).unwrap();
+        assert_eq!(grid_slot_position(FULL_HD, CARD, grid, 7), grid_slot_position(FULL_HD, CARD, grid, 1));
+    }
+
+    #[test]
+    fn random_position_uses_seed_within_margins() {
+        let p = random_position(FULL_HD, CARD, 0);
+        assert_eq!(p, Point::new(RANDOM_MARGIN, RANDOM_MARGIN));
+        let seed = 12345 | (678 << 32);
+        let q = random_position(FULL_HD, CARD, seed);
+        assert_eq!(q, Point::new(RANDOM_MARGIN + (seed % 1321) as f64, RANDOM_MARGIN + 97.0));
+    }
+
+    #[test]
+    fn random_position_survives_tiny_monitors() {
+        let tiny = Rect::new(0.0, 0.0, 300.0, 200.0);
+        assert_eq!(random_position(tiny, CARD, u64::MAX), Point::new(0.0, 0.0));
+    }
+
+    #[test]
+    fn anchors_offset_inwards_from_their_edges() {
+        let offset = Point::new(20.0, 10.0);
+        assert_eq!(anchor_position(FULL_HD, CARD, Anchor::TopLeft, offset), Point::new(20.0, 10.0));
+        assert_eq!(anchor_position(FULL_HD, CARD, Anchor::BottomRight, offset), Point::new(1500.0, 770.0));
+        assert_eq!(anchor_position(FULL_HD, CARD, Anchor::Top, offset), Point::new(780.0, 10.0));
+        assert_eq!(anchor_position(FULL_HD, CARD, Anchor::Center, Point::new(0.0, 0.0)), center_in(FULL_HD, CARD));
+    }
+
+    #[test]
+    fn percent_offsets_scale_with_the_area() {
+        assert_eq!(OffsetUnit::Percent.to_pixels(10.0, 1920.0), 192.0);
+        assert_eq!(OffsetUnit::Px.to_pixels(10.0, 1920.0), 10.0);
+    }
+
+    #[test]
+    fn anchor_offsets_cannot_push_the_window_off_screen() {
+        let p = anchor_position(FULL_HD, CARD, Anchor::Right, Point::new(-500.0, 0.0));
+        assert_eq!(p, Point::new(1520.0, 390.0));
+    }
+
+    #[test]
+    fn anchor_for_picks_the_nearest_edges() {
+        let window = |x: f64, y: f64| Rect::new(x, y, CARD.width, CARD.height);
+        assert_eq!(anchor_for(FULL_HD, window(0.0, 0.0)), (Anchor::TopLeft, Point::new(0.0, 0.0)));
+        assert_eq!(anchor_for(FULL_HD, window(1500.0, 770.0)), (Anchor::BottomRight, Point::new(20.0, 10.0)));
+        assert_eq!(anchor_for(FULL_HD, window(800.0, 0.0)), (Anchor::Top, Point::new(40.0, 0.0)));
+    }
+
+    #[test]
+    fn clamp_into_pins_oversized_windows() {
+        let tiny = Rect::new(10.0, 20.0, 100.0, 100.0);
+        assert_eq!(clamp_into(tiny, CARD, Point::new(500.0, 500.0)), Point::new(10.0, 20.0));
+        assert_eq!(clamp_into(FULL_HD, CARD, Point::new(1800.0, -40.0)), Point::new(1520.0, 0.0));
+    }
+
+    #[test]
+    fn monitor_containing_uses_half_open_edges() {
+        let monitors = [FULL_HD, Rect::new(1920.0, 0.0, 1920.0, 1080.0)];
+        assert_eq!(monitor_containing(&monitors, Point::new(1919.0, 10.0)), Some(0));
+        assert_eq!(monitor_containing(&monitors, Point::new(1920.0, 10.0)), Some(1));
+        assert_eq!(monitor_containing(&monitors, Point::new(-1.0, 10.0)), None);
+    }
+
+    #[test]
+    fn monitor_for_window_falls_back_to_first() {
+        let monitors = [FULL_HD];
+        assert_eq!(monitor_for_window(&monitors, Rect::new(5000.0, 5000.0, 400.0, 300.0)), Some(0));
+        assert_eq!(monitor_for_window(&[], FULL_HD), None);
+    }
+
+    #[test]
+    fn settings_open_right_of_card_when_there_is_room() {
+        let main = Rect::new(100.0, 100.0, 400.0, 300.0);
+        let p = settings_window_position(main, &[at_1x(FULL_HD)], SETTINGS, true).unwrap();
+        assert_eq!(p, Point::new(520.0, 100.0));
+    }
+
+    #[test]
+    fn settings_open_left_of_card_at_right_edge() {
+        let main = Rect::new(1500.0, 100.0, 400.0, 300.0);
+        let p = settings_window_position(main, &[at_1x(FULL_HD)], SETTINGS, true).unwrap();
+        assert_eq!(p, Point::new(1080.0, 100.0));
+    }
+
+    #[test]
+    fn settings_open_below_or_above_a_wide_card() {
+        let narrow = Rect::new(0.0, 0.0, 800.0, 1400.0);
+        let top = Rect::new(0.0, 50.0, 800.0, 300.0);
+        assert_eq!(settings_window_position(top, &[at_1x(narrow)], SETTINGS, true), Some(Point::new(0.0, 370.0)));
+        let bottom = Rect::new(0.0, 1000.0, 800.0, 300.0);
+        assert_eq!(settings_window_position(bottom, &[at_1x(narrow)], SETTINGS, true), Some(Point::new(0.0, 380.0)));
+    }
+
+    #[test]
+    fn monitor_maps_between_logical_and_global_pixels() {
+        let retina = Monitor::new(Rect::new(1920.0, -360.0, 2880.0, 1800.0), 2.0);
+        assert_eq!(retina.logical_area(), Rect::new(0.0, 0.0, 1440.0, 900.0));
+        assert_eq!(retina.to_global(Point::new(100.0, 50.0)), Point::new(2120.0, -260.0));
+        assert_eq!(retina.to_physical(CARD), Size::new(800.0, 600.0));
+        assert_eq!(Monitor::new(FULL_HD, 0.0).scale_factor, 1.0);
+        assert_eq!(Monitor::new(FULL_HD, f64::NAN).scale_factor, 1.0);
+    }
+
+    #[test]
+    fn work_area_is_relative_to_the_monitor_origin() {
+        let monitor = Monitor::new(Rect::new(1920.0, 0.0, 2880.0, 1800.0), 2.0).with_work_area(Rect::new(1920.0, 64.0, 2880.0, 1736.0));
+        assert_eq!(monitor.logical_area(), Rect::new(0.0, 32.0, 1440.0, 868.0));
+        assert_eq!(monitor.to_global(Point::new(0.0, 32.0)), Point::new(1920.0, 64.0));
+    }
+
+    #[test]
+    fn work_area_outside_the_monitor_is_ignored() {
+        let monitor = at_1x(FULL_HD).with_work_area(Rect::new(1920.0, 0.0, 1920.0, 1080.0));
+        assert_eq!(monitor.work_area, FULL_HD);
+        let clipped = at_1x(FULL_HD).with_work_area(Rect::new(-100.0, 30.0, 3000.0, 3000.0));
+        assert_eq!(clipped.work_area, Rect::new(0.0, 30.0, 1920.0, 1050.0));
+    }
+
+    #[test]
+    fn rect_intersection() {
+        let a = Rect::new(0.0, 0.0, 100.0, 100.0);
+        assert_eq!(a.intersection(&Rect::new(50.0, 50.0, 100.0, 100.0)), Some(Rect::new(50.0, 50.0, 50.0, 50.0)));
+        assert_eq!(a.intersection(&Rect::new(100.0, 0.0, 10.0, 10.0)), None);
+    }
+
+    #[test]
+    fn settings_stay_out_of_the_panel() {
+        let with_top_bar = at_1x(FULL_HD).with_work_area(Rect::new(0.0, 32.0, 1920.0, 1048.0));
+        let main = Rect::new(100.0, 0.0, 400.0, 300.0);
+        let p = settings_window_position(main, &[with_top_bar], SETTINGS, false).unwrap();
+        assert_eq!(p, Point::new(520.0, 32.0));
+    }
+
+    #[test]
+    fn settings_gap_scales_with_the_monitor() {
+        let retina = Monitor::new(Rect::new(0.0, 0.0, 2880.0, 1800.0), 2.0);
+        let main = Rect::new(100.0, 100.0, 800.0, 600.0);
+        let p = settings_window_position(main, &[retina], SETTINGS, false).unwrap();
+        assert_eq!(p, Point::new(940.0, 100.0));
+    }
+
+    #[test]
+    fn settings_on_another_monitor_use_its_scale() {
+        let cramped = at_1x(Rect::new(0.0, 0.0, 800.0, 700.0));
+        let retina = Monitor::new(Rect::new(800.0, 0.0, 2880.0, 1800.0), 2.0);
+        let main = Rect::new(0.0, 0.0, 800.0, 700.0);
+        let p = settings_window_position(main, &[cramped, retina], SETTINGS, true).unwrap();
+        // Centred in 1440x900 logical: (520, 150) -> physical offset doubled
+        assert_eq!(p, Point::new(800.0 + 1040.0, 300.0));
+    }
+
+    #[test]
+    fn settings_move_to_another_monitor_only_when_allowed() {
+        let cramped = Rect::new(0.0, 0.0, 800.0, 700.0);
+        let other = Rect::new(800.0, 0.0, 1920.0, 1080.0);
+        let main = Rect::new(0.0, 0.0, 800.0, 700.0);
+
+        let p = settings_window_position(main, &[at_1x(cramped), at_1x(other)], SETTINGS, true).unwrap();
+        assert_eq!(p, center_in(other, SETTINGS));
+
+        let q = settings_window_position(main, &[at_1x(cramped), at_1x(other)], SETTINGS, false).unwrap();
+        assert!(cramped.contains(q));
+    }
+
+    proptest! {
+        #[test]
+        fn grid_positions_stay_inside_area(
+            x in -4000.0..4000.0f64, y in -4000.0..4000.0f64,
+            w in 1.0..8000.0f64, h in 1.0..8000.0f64,
+            win_w in 1.0..1000.0f64, win_h in 1.0..1000.0f64,
+            cols in 1i32..12, rows in 1i32..12, slot in 0u32..1000,
+        ) {
+            let area = Rect::new(x, y, w, h);
+            let window = Size::new(win_w, win_h);
+            let p = grid_slot_position(area, window, Grid::new(cols, rows).unwrap(), slot);
+            if win_w <= w && win_h <= h {
+                prop_assert!(inside(area, window, p));
+            } else {
+                prop_assert!(p.x >= x && p.y >= y);
+            }
+        }
+
+        #[test]
+        fn grid_columns_are_monotonic(cols in 2i32..12, rows in 1i32..12, w in 500.0..8000.0f64) {
+            let grid = Grid::new(cols, rows).unwrap();
+            let area = Rect::new(0.0, 0.0, w, 1080.0);
+            let row: Vec<Point> = (0..cols as u32).map(|c| grid_slot_position(area, CARD, grid, c)).collect();
+            for pair in row.windows(2) {
+                prop_assert!(pair[0].x <= pair[1].x);
+                prop_assert_eq!(pair[0].y, pair[1].y);
+            }
+        }
+
+        #[test]
+        fn anchor_for_round_trips(x in 0.0..1520.0f64, y in 0.0..780.0f64) {
+            let (anchor, offset) = anchor_for(FULL_HD, Rect::new(x, y, CARD.width, CARD.height));
+            let p = anchor_position(FULL_HD, CARD, anchor, offset);
+            prop_assert!((p.x - x).abs() < 1e-6 && (p.y - y).abs() < 1e-6);
+        }
+
+        #[test]
+        fn random_positions_stay_inside_area(
+            x in -4000.0..4000.0f64, y in -4000.0..4000.0f64,
+            w in 400.0..8000.0f64, h in 300.0..8000.0f64,
+            seed in any::<u64>(),
+        ) {
+            let area = Rect::new(x, y, w, h);
+            prop_assert!(inside(area, CARD, random_position(area, CARD, seed)));
+        }
+
+        #[test]
+        fn clamped_windows_stay_inside_area(
+            px in -10000.0..10000.0f64, py in -10000.0..10000.0f64,
+            w in 400.0..8000.0f64, h in 300.0..8000.0f64,
+        ) {
+            let area = Rect::new(0.0, 0.0, w, h);
+            prop_assert!(inside(area, CARD, clamp_into(area, CARD, Point::new(px, py))));
+        }
+
+        #[test]
+        fn settings_window_stays_on_a_monitor(
+            mx in -500.0..4000.0f64, my in -500.0..1500.0f64,
+            second_w in 1600.0..3000.0f64, scale in 1.0..2.0f64, allow in any::<bool>(),
+        ) {
+            let monitors = [at_1x(FULL_HD), Monitor::new(Rect::new(1920.0, 0.0, second_w, 1440.0), scale)];
+            let main = Rect::new(mx, my, 400.0, 300.0);
+            let p = settings_window_position(main, &monitors, SETTINGS, allow).unwrap();
+            prop_assert!(monitors.iter().any(|m| inside(m.bounds, m.to_physical(SETTINGS), p)));
+        }
+
+        #[test]
+        fn settings_window_beside_card_does_not_cover_it(mx in 0.0..1520.0f64, my in 0.0..780.0f64) {
+            let main = Rect::new(mx, my, 400.0, 300.0);
+            let p = settings_window_position(main, &[at_1x(FULL_HD)], SETTINGS, false).unwrap();
+            // Only the last-resort corner placement may overlap the card
+            if p != Point::new(GRID_MARGIN, GRID_MARGIN) {
+                let overlaps = p.x < main.right() && main.x < p.x + SETTINGS.width
+                    && p.y < main.bottom() && main.y < p.y + SETTINGS.height;
+                prop_assert!(!overlaps);
+            }
+        }
+    }
+}
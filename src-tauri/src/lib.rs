@@ -1,6 +1,18 @@
-use tauri::{command, CustomMenuItem, GlobalShortcutManager, Manager, SystemTray, SystemTrayEvent, SystemTrayMenu, SystemTrayMenuItem, WindowEvent};
-use std::sync::{Arc, Mutex};
-use std::collections::HashMap;
+use tauri::{command, Manager, SystemTray, SystemTrayEvent, WindowEvent};
+mod app_state;
+mod dodge;
+mod hot_regions;
+mod layout;
+mod monitor_layout;
+mod placement;
+mod position_rotation;
+mod settings;
+mod shortcuts;
+mod snapping;
+mod timers;
+mod tray;
+mod window_motion;
+mod work_area;
 
 #[cfg(target_os = "macos")]
 use cocoa::base::id;
@@ -9,13 +21,30 @@ use objc::runtime::YES;
 #[cfg(target_os = "macos")]
 use objc::{msg_send, sel, sel_impl};
 
-// Global state to track click-through mode
-type AppState = Arc<Mutex<HashMap<String, bool>>>;
+use app_state::StateStore;
+
+/// Locks managed state, taking the lock over if a panic on another thread
+/// poisoned it. Every piece of state behind these locks is changed by whole
+/// assignments and cached for the UI, so it is never left half-written, and
+/// one panicking thread must not take the tray, shortcuts and settings down
+/// with it.
+pub(crate) fn lock<T>(mutex: &std::sync::Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+/// How long icons stay usable after the temporary access shortcut.
+const REVEAL_DURATION: std::time::Duration = std::time::Duration::from_secs(3);
+const REVEAL_TIMER: &str = "reveal";
 
 #[command]
-fn enable_temporary_icons(app_handle: tauri::AppHandle, state: tauri::State<AppState>) -> bool {
-    let state_map = state.lock().unwrap();
-    let is_click_through = *state_map.get("click_through").unwrap_or(&false);
+fn enable_temporary_icons(app_handle: tauri::AppHandle, state: tauri::State<StateStore>, timers: tauri::State<timers::Timers>) -> bool {
+    let reveal_until = app_state::now_millis() + REVEAL_DURATION.as_millis() as u64;
+    let is_click_through = state.update(&app_handle, |state| {
+        if state.click_through {
+            state.reveal_until = Some(reveal_until);
+        }
+        state.click_through
+    });
     
     if !is_click_through {
         // Not in click-through mode, no need for temporary icon access
@@ -35,82 +64,73 @@ fn enable_temporary_icons(app_handle: tauri::AppHandle, state: tauri::State<AppS
                     let _: () = msg_send![ns_window, setIgnoresMouseEvents: false];
                     println!("✅ Temporary icon access enabled for 3 seconds");
                 }
-                
-                // Set up a timer to disable mouse events again after 3 seconds
-                let app_handle_clone = app_handle.clone();
-                std::thread::spawn(move || {
-                    std::thread::sleep(std::time::Duration::from_secs(3));
-                    
-                    // Re-disable mouse events
-                    if let Some(main_window) = app_handle_clone.get_window("main") {
-                        if let Ok(ns_window) = main_window.ns_window() {
-                            unsafe {
-                                let ns_window: id = ns_window as *mut std::ffi::c_void as id;
-                                let _: () = msg_send![ns_window, setIgnoresMouseEvents: true];
-                                println!("❌ Temporary icon access disabled - back to click-through mode");
-                            }
-                        }
-                    }
-                });
             }
         }
     }
     
+    #[cfg(target_os = "linux")]
+    {
+        set_linux_click_through(&app_handle, false);
+        println!("✅ Temporary icon access enabled for 3 seconds");
+    }
+    
+    // Pressing the shortcut again restarts the countdown
+    let app_handle_clone = app_handle.clone();
+    timers.once(REVEAL_TIMER, REVEAL_DURATION, move || end_reveal(&app_handle_clone));
+    
     true
 }
 
-#[command]
-fn toggle_click_through(app_handle: tauri::AppHandle, state: tauri::State<AppState>) -> bool {
-    let mut state_map = state.lock().unwrap();
-    let current_state = *state_map.get("click_through").unwrap_or(&false);
-    let new_state = !current_state;
-    state_map.insert("click_through".to_string(), new_state);
-    
-    println!("🔄 Toggling click-through: {} -> {}", current_state, new_state);
+/// Ends the temporary icon access and goes back to click-through, unless it
+/// was turned off meanwhile.
+fn end_reveal(app_handle: &tauri::AppHandle) {
+    let still_click_through = app_handle.state::<StateStore>().update(app_handle, |state| {
+        state.reveal_until = None;
+        state.click_through
+    });
+    if !still_click_through {
+        return;
+    }
     
-    // Apply the new click-through state
     #[cfg(target_os = "macos")]
     {
         if let Some(main_window) = app_handle.get_window("main") {
             if let Ok(ns_window) = main_window.ns_window() {
                 unsafe {
                     let ns_window: id = ns_window as *mut std::ffi::c_void as id;
-                    
-                    if new_state {
-                        // Enable click-through
-                        let _: () = msg_send![ns_window, setIgnoresMouseEvents: true];
-                        let _: () = msg_send![ns_window, setAcceptsMouseMovedEvents: false];
-                        let _: () = msg_send![ns_window, setMovableByWindowBackground: false];
-                        let level: i32 = 3; // NSStatusWindowLevel
-                        let _: () = msg_send![ns_window, setLevel: level];
-                        println!("✅ Click-through mode ENABLED via shortcut");
-                    } else {
-                        // Disable click-through
-                        let _: () = msg_send![ns_window, setIgnoresMouseEvents: false];
-                        let _: () = msg_send![ns_window, setAcceptsMouseMovedEvents: true];
-                        let _: () = msg_send![ns_window, setMovableByWindowBackground: true];
-                        let level: i32 = 5; // NSFloatingWindowLevel
-                        let _: () = msg_send![ns_window, setLevel: level];
-                        println!("✅ Click-through mode DISABLED via shortcut");
-                    }
+                    let _: () = msg_send![ns_window, setIgnoresMouseEvents: true];
                 }
             }
         }
     }
     
-    // Emit event to update frontend state
-    let _ = app_handle.emit_all("click-through-toggled", new_state);
+    #[cfg(target_os = "linux")]
+    set_linux_click_through(app_handle, true);
+    
+    println!("❌ Temporary icon access disabled - back to click-through mode");
+}
+
+#[command]
+fn toggle_click_through(app_handle: tauri::AppHandle, state: tauri::State<StateStore>) -> bool {
+    let current_state = state.get().click_through;
+    let new_state = !current_state;
+    
+    println!("🔄 Toggling click-through: {} -> {}", current_state, new_state);
+    set_click_through(app_handle, new_state, state);
     
     new_state
 }
 
 #[command]
-fn set_click_through(app_handle: tauri::AppHandle, click_through: bool, state: tauri::State<AppState>) {
+fn set_click_through(app_handle: tauri::AppHandle, click_through: bool, state: tauri::State<StateStore>) {
     println!("set_click_through called with click_through: {}", click_through);
     
     // Update state
-    let mut state_map = state.lock().unwrap();
-    state_map.insert("click_through".to_string(), click_through);
+    state.update(&app_handle, |state| {
+        state.click_through = click_through;
+        state.reveal_until = None;
+    });
+    app_handle.state::<timers::Timers>().cancel(REVEAL_TIMER);
     
     #[cfg(target_os = "macos")]
     {
@@ -151,12 +171,35 @@ fn set_click_through(app_handle: tauri::AppHandle, click_through: bool, state: t
         }
     }
     
-    // Emit event to sync with UI (for consistency with toggle command)
+    #[cfg(target_os = "linux")]
+    set_linux_click_through(&app_handle, click_through);
+    
+    // Remember the mode across restarts, whether it came from the shortcut, tray or a window
+    let store = app_handle.state::<settings::SettingsStore>();
+    if store.get().click_through != click_through {
+        if let Err(e) = store.update(&app_handle, &serde_json::json!({ "clickThrough": click_through })) {
+            println!("⚠️ Failed to save click-through mode: {}", e);
+        }
+    }
+    
+    // Emit event so the tray, shortcut and every window agree on the mode
     let _ = app_handle.emit_all("click-through-toggled", click_through);
     
-    #[cfg(not(target_os = "macos"))]
+    #[cfg(not(any(target_os = "macos", target_os = "linux")))]
     {
-        println!("set_click_through: Not on macOS, ignoring");
+        println!("set_click_through: Not supported on this platform, ignoring");
+    }
+}
+
+/// Makes the main window ignore or accept the mouse, apart from its hot
+/// regions. GTK does this with an input shape, so it works on X11 and
+/// Wayland alike.
+#[cfg(target_os = "linux")]
+fn set_linux_click_through(app_handle: &tauri::AppHandle, click_through: bool) {
+    match hot_regions::apply(app_handle, click_through) {
+        Ok(()) if click_through => println!("✅ Click-through mode ENABLED - window ignores all mouse events"),
+        Ok(()) => println!("✅ Click-through mode DISABLED"),
+        Err(e) => println!("⚠️ Failed to change click-through mode: {}", e),
     }
 }
 
@@ -201,242 +244,108 @@ fn update_window_spaces(app_handle: tauri::AppHandle, show_on_all_spaces: bool)
 }
 
 #[command]
-fn set_window_position(app_handle: tauri::AppHandle, grid_position: i32, random_position: bool, manual_position: bool, manual_x: f64, manual_y: f64, auto_detect_grid: bool, manual_grid_cols: i32, manual_grid_rows: i32, preferred_monitor: String) {
-    use tauri::LogicalPosition;
-    
-    // Get target monitor based on preference
-    let (screen_size, screen_offset) = if let Some(main_window) = app_handle.get_window("main") {
-        if let Ok(all_monitors) = main_window.available_monitors() {
-            // Get current monitor info for comparison
-            let current_monitor_pos = if let Ok(current_monitor) = main_window.current_monitor() {
-                if let Some(monitor) = current_monitor {
-                    let pos = monitor.position();
-                    Some((pos.x as f64, pos.y as f64))
-                } else {
-                    None
-                }
-            } else {
-                None
-            };
-            
-            let target_monitor = match preferred_monitor.as_str() {
-                "primary" => {
-                    // Use first monitor (typically primary)
-                    if !all_monitors.is_empty() {
-                        Some(&all_monitors[0])
-                    } else {
-                        None
-                    }
-                },
-                "current" => {
-                    // Use current monitor where window is by finding it in all_monitors
-                    if let Some((curr_x, curr_y)) = current_monitor_pos {
-                        all_monitors.iter().find(|monitor| {
-                            let pos = monitor.position();
-                            pos.x as f64 == curr_x && pos.y as f64 == curr_y
-                        })
-                    } else {
-                        None
-                    }
-                },
-                monitor_index if monitor_index.parse::<usize>().is_ok() => {
-                    // Use specific monitor by index
-                    let idx = monitor_index.parse::<usize>().unwrap();
-                    if idx < all_monitors.len() {
-                        Some(&all_monitors[idx])
-                    } else {
-                        None
-                    }
-                },
-                _ => {
-                    // "auto" or unknown - use current monitor
-                    if let Some((curr_x, curr_y)) = current_monitor_pos {
-                        all_monitors.iter().find(|monitor| {
-                            let pos = monitor.position();
-                            pos.x as f64 == curr_x && pos.y as f64 == curr_y
-                        })
-                    } else {
-                        None
-                    }
-                }
-            };
-            
-            if let Some(monitor) = target_monitor {
-                let size = monitor.size();
-                let pos = monitor.position();
-                ((size.width as f64, size.height as f64), (pos.x as f64, pos.y as f64))
-            } else {
-                // Fallback to first available monitor or default
-                if !all_monitors.is_empty() {
-                    let monitor = &all_monitors[0];
-                    let size = monitor.size();
-                    let pos = monitor.position();
-                    ((size.width as f64, size.height as f64), (pos.x as f64, pos.y as f64))
-                } else {
-                    ((1920.0, 1080.0), (0.0, 0.0)) // Fallback
-                }
-            }
-        } else {
-            ((1920.0, 1080.0), (0.0, 0.0)) // Fallback
-        }
-    } else {
-        ((1920.0, 1080.0), (0.0, 0.0)) // Fallback
-    };
-    
-    let window_size = (400.0, 300.0); // Window dimensions from config
-    
-    let (x, y) = if manual_position {
-        // Use manual position set by dragging (already in screen coordinates)
-        (manual_x, manual_y)
-    } else if random_position {
-        use std::collections::hash_map::DefaultHasher;
-        use std::hash::{Hash, Hasher};
-        use std::time::{SystemTime, UNIX_EPOCH};
-        
-        // Generate random position using time as seed
-        let mut hasher = DefaultHasher::new();
-        SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos().hash(&mut hasher);
-        let hash = hasher.finish();
-        
-        let margin = 100.0;
-        let max_x = screen_size.0 - window_size.0 - margin;
-        let max_y = screen_size.1 - window_size.1 - margin;
-        
-        let relative_x = margin + ((hash % max_x as u64) as f64);
-        let relative_y = margin + (((hash >> 32) % max_y as u64) as f64);
-        
-        // Add monitor offset for correct monitor positioning
-        let x = screen_offset.0 + relative_x;
-        let y = screen_offset.1 + relative_y;
-        (x, y)
-    } else {
-        // Calculate grid dimensions - use manual override or auto-detect
-        let (cols, rows) = if auto_detect_grid {
-            let aspect_ratio = screen_size.0 / screen_size.1;
-            println!("Auto-detecting grid based on aspect ratio: {}", aspect_ratio);
-            
-            if aspect_ratio > 2.0 {
-                println!("Ultra-wide detected: 6x3 grid");
-                (6, 3)
-            } else if aspect_ratio > 1.8 {
-                println!("Wide screen detected: 5x3 grid");
-                (5, 3)
-            } else if aspect_ratio > 1.5 {
-                println!("Standard wide detected: 4x3 grid");
-                (4, 3)
-            } else {
-                println!("Square-ish screen detected: 3x4 grid");
-                (3, 4)
-            }
-        } else {
-            println!("Using manual grid override: {}x{}", manual_grid_cols, manual_grid_rows);
-            (manual_grid_cols, manual_grid_rows)
-        };
-        
-        let total_positions = cols * rows;
-        let safe_position = grid_position % total_positions; // Ensure we don't overflow
-        
-        let row = safe_position / cols;
-        let col = safe_position % cols;
-        
-        println!("Grid position {} -> row:{}, col:{} in {}x{} grid", grid_position, row, col, cols, rows);
-        
-        let margin = 50.0;
-        let available_width = screen_size.0 - window_size.0 - (2.0 * margin);
-        let available_height = screen_size.1 - window_size.1 - (2.0 * margin);
-        
-        // For single column/row, center it. Otherwise distribute evenly
-        let relative_x = if cols == 1 {
-            margin + available_width / 2.0
-        } else {
-            margin + (col as f64 * available_width / (cols - 1) as f64)
-        };
-        
-        let relative_y = if rows == 1 {
-            margin + available_height / 2.0
-        } else {
-            margin + (row as f64 * available_height / (rows - 1) as f64)
-        };
-        
-        // Add monitor offset to position on correct monitor
-        let x = screen_offset.0 + relative_x;
-        let y = screen_offset.1 + relative_y;
-        (x, y)
-    };
-    
-    // Get the main window specifically
-    if let Some(main_window) = app_handle.get_window("main") {
-        println!("Monitor: {}x{} at offset ({}, {}), Setting position to ({}, {}) - grid:{}, random:{}, manual:{}, monitor:{}", 
-                screen_size.0, screen_size.1, screen_offset.0, screen_offset.1, x, y, grid_position, random_position, manual_position, preferred_monitor);
-        let _ = main_window.set_position(LogicalPosition::new(x, y));
-    } else {
-        println!("Warning: Could not find main window to set position");
-    }
+fn set_window_position(app_handle: tauri::AppHandle, request: placement::PlacementRequest) -> Result<(), placement::PlacementError> {
+    let motion = window_motion::Motion::from_settings(&app_handle.state::<settings::SettingsStore>().get());
+    place_main_window(&app_handle, &request, motion)
 }
 
-#[command]
-fn get_all_monitors_info(app_handle: tauri::AppHandle) -> Vec<(i32, f64, f64, f64, f64, bool)> {
-    let mut monitors_info = Vec::new();
+/// Moves the main window to `request`, cancelling any move still in flight.
+fn place_main_window(app_handle: &tauri::AppHandle, request: &placement::PlacementRequest, motion: window_motion::Motion) -> Result<(), placement::PlacementError> {
+    let (main_window, position) = resolve_main_window_position(app_handle, request)?;
+    dodge::clear(app_handle);
+    app_handle
+        .state::<window_motion::WindowMotion>()
+        .move_to(&main_window, position, motion)
+        .map_err(|e| placement::PlacementError::window_unavailable(format!("Failed to move main window: {}", e)))
+}
+
+/// Where `request` would put the main window, in global physical pixels,
+/// without moving it.
+fn resolve_main_window_position(
+    app_handle: &tauri::AppHandle,
+    request: &placement::PlacementRequest,
+) -> Result<(tauri::Window, tauri::PhysicalPosition<i32>), placement::PlacementError> {
+    request.validate()?;
+    
+    let main_window = app_handle
+        .get_window("main")
+        .ok_or_else(|| placement::PlacementError::window_unavailable("Could not find main window to set position"))?;
+    
+    let monitor = target_monitor(&main_window, &request.monitor())
+        .unwrap_or(layout::Monitor::new(layout::Rect::new(0.0, 0.0, 1920.0, 1080.0), 1.0));
+    let window_size = logical_window_size(&main_window).unwrap_or(layout::Size::new(400.0, 300.0));
+    
+    let position = request.resolve(&monitor, window_size, clock_seed())?;
+    let area = monitor.bounds;
+    println!("Monitor: {}x{} @{}x at offset ({}, {}), window {}x{}, Setting position to ({}, {}) - {:?}", 
+            area.width, area.height, monitor.scale_factor, area.x, area.y, window_size.width, window_size.height, position.x, position.y, request);
+    Ok((main_window, tauri::PhysicalPosition::new(position.x.round() as i32, position.y.round() as i32)))
+}
+
+/// A seed for random placement, taken from the clock.
+fn clock_seed() -> u64 {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+    use std::time::{SystemTime, UNIX_EPOCH};
+    
+    let mut hasher = DefaultHasher::new();
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_nanos().hash(&mut hasher);
+    hasher.finish()
+}
+
+/// The monitor selected by `preference`. A preferred monitor that is no
+/// longer connected falls back to the primary monitor.
+fn target_monitor(main_window: &tauri::Window, preference: &settings::PreferredMonitor) -> Option<layout::Monitor> {
+    let all_monitors = main_window.available_monitors().ok()?;
+    let descriptors: Vec<monitor_layout::MonitorDescriptor> = all_monitors.iter().map(monitor_layout::MonitorDescriptor::from_monitor).collect();
+    let find = |monitor: Option<tauri::Monitor>| {
+        monitor.and_then(|monitor| monitor_layout::find_monitor(&descriptors, &monitor_layout::MonitorDescriptor::from_monitor(&monitor)))
+    };
+    let primary = find(main_window.primary_monitor().ok().flatten());
+    let current = find(main_window.current_monitor().ok().flatten());
     
-    if let Some(main_window) = app_handle.get_window("main") {
-        if let Ok(all_monitors) = main_window.available_monitors() {
-            for (index, monitor) in all_monitors.iter().enumerate() {
-                let size = monitor.size();
-                let position = monitor.position();
-                let is_primary = index == 0; // First monitor is typically primary
-                
-                monitors_info.push((
-                    index as i32,
-                    position.x as f64,
-                    position.y as f64,
-                    size.width as f64,
-                    size.height as f64,
-                    is_primary
-                ));
-            }
+    let index = monitor_layout::resolve_preference(&descriptors, primary, current, preference)?;
+    if let settings::PreferredMonitor::Monitor(wanted) = preference {
+        if descriptors[index] != *wanted {
+            println!("🖥️ Preferred monitor {} is not connected, using {}", wanted, descriptors[index]);
         }
     }
-    
-    if monitors_info.is_empty() {
-        // Fallback if no monitors detected
-        monitors_info.push((0, 0.0, 0.0, 1920.0, 1080.0, true));
-    }
-    
-    monitors_info
+    Some(layout_monitor(&all_monitors[index], &work_area::WorkAreas::current()))
+}
+
+/// The window's current outer size in logical pixels, which stays the same
+/// when it moves to a monitor with a different scale factor.
+fn logical_window_size(window: &tauri::Window) -> Option<layout::Size> {
+    let size = window.outer_size().ok()?;
+    let scale_factor = window.scale_factor().ok()?;
+    let logical = size.to_logical::<f64>(scale_factor);
+    Some(layout::Size::new(logical.width, logical.height))
 }
 
 #[command]
 fn get_screen_info(app_handle: tauri::AppHandle, auto_detect_grid: bool, manual_grid_cols: i32, manual_grid_rows: i32) -> (f64, f64, i32, i32) {
-    if let Some(main_window) = app_handle.get_window("main") {
-        if let Ok(monitor) = main_window.current_monitor() {
-            if let Some(monitor) = monitor {
-                let size = monitor.size();
-                let aspect_ratio = size.width as f64 / size.height as f64;
-                
-                let (cols, rows) = if auto_detect_grid {
-                    // Auto-detect based on aspect ratio
-                    let grid = if aspect_ratio > 2.0 {
-                        (6, 3) // Ultra-wide
-                    } else if aspect_ratio > 1.8 {
-                        (5, 3) // Wide
-                    } else if aspect_ratio > 1.5 {
-                        (4, 3) // Standard
-                    } else {
-                        (3, 4) // Square-ish
-                    };
-                    println!("get_screen_info: Auto-detecting grid {}x{} for aspect ratio {:.2}", grid.0, grid.1, aspect_ratio);
-                    grid
-                } else {
-                    // Use manual grid override
-                    println!("get_screen_info: Using manual grid override {}x{}", manual_grid_cols, manual_grid_rows);
-                    (manual_grid_cols, manual_grid_rows)
-                };
-                
-                return (size.width as f64, size.height as f64, cols, rows);
-            }
-        }
-    }
-    (1920.0, 1080.0, 4, 3) // Fallback
+    let monitor = current_monitor(&app_handle).unwrap_or(layout::Monitor::new(layout::Rect::new(0.0, 0.0, 1920.0, 1080.0), 1.0));
+    // The work area, not the whole monitor: it is what the grid divides up,
+    // so the preview has the same proportions as the slots placement uses
+    let area = monitor.work_area.size();
+    let grid = layout::grid_dimensions(area, auto_detect_grid, manual_grid_cols, manual_grid_rows);
+    println!("get_screen_info: Using grid {}x{} for aspect ratio {:.2}", grid.cols(), grid.rows(), area.aspect_ratio());
+    (area.width, area.height, grid.cols() as i32, grid.rows() as i32)
+}
+
+/// Physical bounds, work area and scale factor of a monitor.
+fn layout_monitor(monitor: &tauri::Monitor, work_areas: &work_area::WorkAreas) -> layout::Monitor {
+    let position = monitor.position();
+    let size = monitor.size();
+    let bounds = layout::Rect::new(position.x as f64, position.y as f64, size.width as f64, size.height as f64);
+    layout::Monitor::new(bounds, monitor.scale_factor()).with_work_area(work_areas.for_monitor(bounds))
+}
+
+/// The monitor the main window is on.
+fn current_monitor(app_handle: &tauri::AppHandle) -> Option<layout::Monitor> {
+    let main_window = app_handle.get_window("main")?;
+    let monitor = main_window.current_monitor().ok()??;
+    Some(layout_monitor(&monitor, &work_area::WorkAreas::current()))
 }
 
 #[command]
@@ -453,120 +362,28 @@ fn save_manual_position(app_handle: tauri::AppHandle) -> (f64, f64) {
 }
 
 #[command]
-fn show_settings_window(app_handle: tauri::AppHandle) {
+fn show_settings_window(app_handle: tauri::AppHandle, store: tauri::State<settings::SettingsStore>) {
     if let Some(settings_window) = app_handle.get_window("settings") {
         // Get main window position and size
         if let Some(main_window) = app_handle.get_window("main") {
             if let (Ok(main_pos), Ok(main_size)) = (main_window.outer_position(), main_window.outer_size()) {
-                // Get all available monitors
-                if let Ok(all_monitors) = main_window.available_monitors() {
-                    // Find which monitor the main window is on
-                    let main_x = main_pos.x as f64;
-                    let main_y = main_pos.y as f64;
-                    let main_w = main_size.width as f64;
-                    let main_h = main_size.height as f64;
-                    let main_center_x = main_x + main_w / 2.0;
-                    let main_center_y = main_y + main_h / 2.0;
-                    
-                    let mut target_monitor = None;
-                    
-                    // Find the monitor containing the center of the main window
-                    for monitor in &all_monitors {
-                        let mon_pos = monitor.position();
-                        let mon_size = monitor.size();
-                        let mon_x = mon_pos.x as f64;
-                        let mon_y = mon_pos.y as f64;
-                        let mon_w = mon_size.width as f64;
-                        let mon_h = mon_size.height as f64;
-                        
-                        if main_center_x >= mon_x && main_center_x < mon_x + mon_w &&
-                           main_center_y >= mon_y && main_center_y < mon_y + mon_h {
-                            target_monitor = Some((mon_x, mon_y, mon_w, mon_h));
-                            break;
-                        }
-                    }
-                    
-                    // Fallback to first monitor if main window monitor not found
-                    if target_monitor.is_none() && !all_monitors.is_empty() {
-                        let monitor = &all_monitors[0];
-                        let mon_pos = monitor.position();
-                        let mon_size = monitor.size();
-                        target_monitor = Some((mon_pos.x as f64, mon_pos.y as f64, mon_size.width as f64, mon_size.height as f64));
-                    }
-                    
-                    if let Some((screen_x, screen_y, screen_w, screen_h)) = target_monitor {
-                        // Settings window size (from tauri.conf.json)
-                        let settings_width = 400.0;
-                        let settings_height = 600.0;
-                        
-                        // Try different positions in order of preference
-                        let mut settings_x = screen_x + (screen_w - settings_width) / 2.0; // default center
-                        let mut settings_y = screen_y + (screen_h - settings_height) / 2.0;
-                        
-                        let gap = 20.0;
-                        let buffer = 50.0; // Extra space to ensure fit
-                        
-                        // 1. Try to the right of main window (same monitor)
-                        if main_x + main_w + settings_width + gap + buffer <= screen_x + screen_w {
-                            settings_x = main_x + main_w + gap;
-                            settings_y = main_y.max(screen_y).min(screen_y + screen_h - settings_height);
-                        }
-                        // 2. Try to the left of main window (same monitor)
-                        else if main_x - settings_width - gap >= screen_x + buffer {
-                            settings_x = main_x - settings_width - gap;
-                            settings_y = main_y.max(screen_y).min(screen_y + screen_h - settings_height);
-                        }
-                        // 3. Try below main window (same monitor)
-                        else if main_y + main_h + settings_height + gap <= screen_y + screen_h - buffer {
-                            settings_x = main_x.max(screen_x).min(screen_x + screen_w - settings_width);
-                            settings_y = main_y + main_h + gap;
-                        }
-                        // 4. Try above main window (same monitor)
-                        else if main_y - settings_height - gap >= screen_y + buffer {
-                            settings_x = main_x.max(screen_x).min(screen_x + screen_w - settings_width);
-                            settings_y = main_y - settings_height - gap;
-                        }
-                        // 5. Try on a different monitor if available and allowed
-                        else if all_monitors.len() > 1 {
-                            // Find best alternative monitor
-                            for (i, monitor) in all_monitors.iter().enumerate() {
-                                let mon_pos = monitor.position();
-                                let mon_size = monitor.size();
-                                let alt_x = mon_pos.x as f64;
-                                let alt_y = mon_pos.y as f64;
-                                let alt_w = mon_size.width as f64;
-                                let alt_h = mon_size.height as f64;
-                                
-                                // Skip the current monitor
-                                if (alt_x, alt_y) == (screen_x, screen_y) {
-                                    continue;
-                                }
-                                
-                                // Check if settings window fits comfortably on this monitor
-                                if alt_w >= settings_width + 100.0 && alt_h >= settings_height + 100.0 {
-                                    settings_x = alt_x + (alt_w - settings_width) / 2.0;
-                                    settings_y = alt_y + (alt_h - settings_height) / 2.0;
-                                    println!("Settings positioned on alternative monitor {} due to space constraints", i);
-                                    break;
-                                }
-                            }
-                        }
-                        // 6. Final fallback: offset from corner of current monitor
-                        else {
-                            settings_x = screen_x + 50.0;
-                            settings_y = screen_y + 50.0;
-                        }
-                        
-                        // Ensure settings window stays within the chosen screen bounds
-                        settings_x = settings_x.max(screen_x).min(screen_x + screen_w - settings_width);
-                        settings_y = settings_y.max(screen_y).min(screen_y + screen_h - settings_height);
-                        
-                        // Position the settings window
-                        let _ = settings_window.set_position(tauri::LogicalPosition::new(settings_x, settings_y));
-                        
-                        println!("Settings positioned at ({:.1}, {:.1}) to avoid main window at ({:.1}, {:.1}) on monitor {}x{}", 
-                                settings_x, settings_y, main_x, main_y, screen_w, screen_h);
-                    }
+                let main = layout::Rect::new(main_pos.x as f64, main_pos.y as f64, main_size.width as f64, main_size.height as f64);
+                let monitors: Vec<layout::Monitor> = main_window
+                    .available_monitors()
+                    .map(|monitors| {
+                        let work_areas = work_area::WorkAreas::current();
+                        monitors.iter().map(|monitor| layout_monitor(monitor, &work_areas)).collect()
+                    })
+                    .unwrap_or_default();
+                
+                // Fall back to the size from tauri.conf.json
+                let settings_size = logical_window_size(&settings_window).unwrap_or(layout::Size::new(400.0, 600.0));
+                let allow_other_monitor = store.get().allow_cross_monitor_positioning;
+                
+                if let Some(position) = layout::settings_window_position(main, &monitors, settings_size, allow_other_monitor) {
+                    let _ = settings_window.set_position(tauri::PhysicalPosition::new(position.x.round() as i32, position.y.round() as i32));
+                    println!("Settings positioned at ({:.1}, {:.1}) to avoid main window at ({:.1}, {:.1})",
+                            position.x, position.y, main.x, main.y);
                 } else {
                     // Fallback if monitor detection fails
                     let _ = settings_window.center();
@@ -594,38 +411,20 @@ fn show_main_window(app_handle: tauri::AppHandle) {
         
         // Force window to be visible
         let _ = window.show();
+        set_window_mode(&app_handle, app_state::WindowMode::Visible);
         let _ = window.unminimize();
         let _ = window.set_resizable(true);
         
-        // Force position to center of main screen (not current monitor)
-        let window_width = 450.0;
-        let window_height = 220.0;
-        
-        // Get all monitors and use the primary one
-        if let Ok(monitors) = window.available_monitors() {
-            if let Some(primary_monitor) = monitors.into_iter().next() {
-                let monitor_size = primary_monitor.size();
-                let monitor_pos = primary_monitor.position();
-                
-                // Force to center of primary monitor
-                let x = monitor_pos.x as f64 + (monitor_size.width as f64 - window_width) / 2.0;
-                let y = monitor_pos.y as f64 + (monitor_size.height as f64 - window_height) / 2.0;
-                
-                println!("Forcing main window to ({}, {}) on primary monitor {}x{}", x, y, monitor_size.width, monitor_size.height);
-                let _ = window.set_position(tauri::LogicalPosition::new(x, y));
-            }
-        } else {
-            // Fallback: force to absolute screen center
-            let x = 100.0;
-            let y = 100.0;
-            println!("Fallback: Forcing main window to ({}, {})", x, y);
-            let _ = window.set_position(tauri::LogicalPosition::new(x, y));
+        // Place it like every other path does: the configured position on the
+        // preferred monitor, using the window's real size and scale factor
+        let settings = app_handle.state::<settings::SettingsStore>().get();
+        if let Err(e) = place_main_window(&app_handle, &placement::PlacementRequest::from_settings(&settings), window_motion::Motion::INSTANT) {
+            println!("⚠️ Failed to place main window: {}", e);
         }
         
         // Ensure it's on top and focused
         let _ = window.set_always_on_top(true);
         let _ = window.set_focus();
-        let _ = window.set_size(tauri::LogicalSize::new(window_width, window_height));
         
         // Final position check
         if let Ok(final_pos) = window.outer_position() {
@@ -655,10 +454,28 @@ fn show_main_window(app_handle: tauri::AppHandle) {
     }
 }
 
+/// Hides the main window, or shows it again where it was.
+fn toggle_main_window(app_handle: &tauri::AppHandle) {
+    let Some(window) = app_handle.get_window("main") else {
+        return;
+    };
+    let visible = window.is_visible().unwrap_or(true);
+    let result = if visible { window.hide() } else { window.show() };
+    match result {
+        Ok(()) => set_window_mode(app_handle, if visible { app_state::WindowMode::Hidden } else { app_state::WindowMode::Visible }),
+        Err(e) => println!("⚠️ Failed to toggle main window: {}", e),
+    }
+}
+
+/// Records whether the main window is on screen.
+fn set_window_mode(app_handle: &tauri::AppHandle, mode: app_state::WindowMode) {
+    app_handle.state::<StateStore>().update(app_handle, |state| state.window_mode = mode);
+}
+
 #[command]
-fn initialize_window_position(app_handle: tauri::AppHandle, grid_position: i32, random_position: bool, manual_position: bool, manual_x: f64, manual_y: f64, auto_detect_grid: bool, manual_grid_cols: i32, manual_grid_rows: i32, preferred_monitor: String) {
-    // Call the existing set_window_position function to set initial position based on saved settings
-    set_window_position(app_handle, grid_position, random_position, manual_position, manual_x, manual_y, auto_detect_grid, manual_grid_cols, manual_grid_rows, preferred_monitor);
+fn initialize_window_position(app_handle: tauri::AppHandle, request: placement::PlacementRequest) -> Result<(), placement::PlacementError> {
+    // The first placement should not visibly slide in from the centre
+    place_main_window(&app_handle, &request, window_motion::Motion::INSTANT)
 }
 
 #[command]
@@ -679,37 +496,65 @@ fn resize_window_for_content(app_handle: tauri::AppHandle, content_width: f64, c
 }
 
 #[command]
-async fn check_ollama_connection() -> Result<String, String> {
+async fn check_ollama_connection(
+    app_handle: tauri::AppHandle,
+    store: tauri::State<'_, settings::SettingsStore>,
+    state: tauri::State<'_, StateStore>,
+) -> Result<String, String> {
     use reqwest::Client;
     
-    println!("📡 Checking Ollama connection...");
+    // Provider, endpoint and model are part of the active profile's AI settings
+    let settings = store.get();
+    if settings.ai_provider != "ollama" {
+        let error_msg = format!("❌ Unsupported AI provider: {}", settings.ai_provider);
+        println!("{}", error_msg);
+        state.update(&app_handle, |state| state.provider_status = app_state::ProviderStatus::Offline);
+        return Err(error_msg);
+    }
+    let endpoint = settings.ai_endpoint.trim_end_matches('/');
+    println!("📡 Checking Ollama connection at {}...", endpoint);
     
     let client = Client::new();
     
-    // Try to connect to Ollama API
-    match client
-        .get("http://localhost:11434/api/version")
+    // Ollama lists its installed models under /api/tags, which also proves it is up
+    let result = match client
+        .get(format!("{}/api/tags", endpoint))
         .timeout(std::time::Duration::from_secs(5))
         .send()
         .await
     {
-        Ok(response) => {
-            if response.status().is_success() {
-                let msg = "✅ Ollama connection successful";
+        Ok(response) if response.status().is_success() => {
+            let tags: serde_json::Value = response.json().await.unwrap_or_default();
+            // Tags without a version, like "llama3", mean "llama3:latest"
+            let installed = tags["models"].as_array().into_iter().flatten().any(|model| {
+                let name = model["name"].as_str().unwrap_or_default();
+                name == settings.ai_model || name == format!("{}:latest", settings.ai_model)
+            });
+            if installed {
+                let msg = format!("✅ Ollama connection successful, model {} available", settings.ai_model);
                 println!("{}", msg);
-                Ok(msg.to_string())
+                Ok(msg)
             } else {
-                let error_msg = format!("❌ Ollama returned status: {}", response.status());
+                let error_msg = format!("❌ Ollama is running but model {} is not installed", settings.ai_model);
                 println!("{}", error_msg);
                 Err(error_msg)
             }
         }
+        Ok(response) => {
+            let error_msg = format!("❌ Ollama returned status: {}", response.status());
+            println!("{}", error_msg);
+            Err(error_msg)
+        }
         Err(e) => {
             let error_msg = format!("❌ Failed to connect to Ollama: {}", e);
             println!("{}", error_msg);
             Err(error_msg)
         }
-    }
+    };
+    
+    let status = if result.is_ok() { app_state::ProviderStatus::Online } else { app_state::ProviderStatus::Offline };
+    state.update(&app_handle, |state| state.provider_status = status);
+    result
 }
 
 #[command]
@@ -717,6 +562,10 @@ fn test_drag(app_handle: tauri::AppHandle) -> Result<String, String> {
     println!("👍 Test drag command called");
     
     if let Some(window) = app_handle.get_window("main") {
+        // The user takes over; stop any animated move and snap once dropped
+        app_handle.state::<window_motion::WindowMotion>().cancel();
+        snapping::drag_started(&app_handle);
+        
         // Try to start dragging
         match window.start_dragging() {
             Ok(_) => {
@@ -789,77 +638,109 @@ fn quit_app(app_handle: tauri::AppHandle) -> Result<String, String> {
 
 #[command]
 fn debug_positions(app_handle: tauri::AppHandle, auto_detect_grid: bool, manual_grid_cols: i32, manual_grid_rows: i32) -> Vec<(i32, f64, f64)> {
-    let mut positions = Vec::new();
-    
-    // Get screen info
-    let (screen_width, screen_height, cols, rows) = if let Some(main_window) = app_handle.get_window("main") {
-        if let Ok(monitor) = main_window.current_monitor() {
-            if let Some(monitor) = monitor {
-                let size = monitor.size();
-                let aspect_ratio = size.width as f64 / size.height as f64;
-                
-                let (cols, rows) = if auto_detect_grid {
-                    // Auto-detect based on aspect ratio
-                    if aspect_ratio > 2.0 {
-                        (6, 3)
-                    } else if aspect_ratio > 1.8 {
-                        (5, 3)
-                    } else if aspect_ratio > 1.5 {
-                        (4, 3)
-                    } else {
-                        (3, 4)
-                    }
-                } else {
-                    // Use manual grid override
-                    (manual_grid_cols, manual_grid_rows)
-                };
-                
-                (size.width as f64, size.height as f64, cols, rows)
-            } else {
-                let fallback_cols = if auto_detect_grid { 4 } else { manual_grid_cols };
-                let fallback_rows = if auto_detect_grid { 3 } else { manual_grid_rows };
-                (1920.0, 1080.0, fallback_cols, fallback_rows)
-            }
-        } else {
-            let fallback_cols = if auto_detect_grid { 4 } else { manual_grid_cols };
-            let fallback_rows = if auto_detect_grid { 3 } else { manual_grid_rows };
-            (1920.0, 1080.0, fallback_cols, fallback_rows)
-        }
-    } else {
-        let fallback_cols = if auto_detect_grid { 4 } else { manual_grid_cols };
-        let fallback_rows = if auto_detect_grid { 3 } else { manual_grid_rows };
-        (1920.0, 1080.0, fallback_cols, fallback_rows)
-    };
-    
-    println!("Debug: Screen {}x{}, Grid {}x{}, Aspect: {}", screen_width, screen_height, cols, rows, screen_width / screen_height);
+    let monitor = current_monitor(&app_handle).unwrap_or(layout::Monitor::new(layout::Rect::new(0.0, 0.0, 1920.0, 1080.0), 1.0));
+    let window_size = app_handle
+        .get_window("main")
+        .and_then(|window| logical_window_size(&window))
+        .unwrap_or(layout::Size::new(400.0, 300.0));
+    
+    // Positions are logical and relative to the monitor origin
+    let area = monitor.logical_area();
+    let grid = layout::grid_dimensions(area.size(), auto_detect_grid, manual_grid_cols, manual_grid_rows);
+    
+    println!("Debug: Screen {}x{} @{}x, Window {}x{}, Grid {}x{}, Aspect: {}", area.width, area.height, monitor.scale_factor,
+            window_size.width, window_size.height, grid.cols(), grid.rows(), area.size().aspect_ratio());
+    
+    layout::grid_positions(area, window_size, grid)
+        .into_iter()
+        .enumerate()
+        .map(|(slot, position)| {
+            println!("Position {}: ({}, {})", slot, position.x, position.y);
+            (slot as i32, position.x, position.y)
+        })
+        .collect()
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AnchorPreview {
+    /// Logical work area of the current monitor, relative to its top-left corner
+    area: layout::Rect,
+    window: layout::Size,
+    positions: Vec<(layout::Anchor, f64, f64)>,
+}
+
+/// Where the card would land for each anchor with the given offsets, for the
+/// settings preview. Positions are logical, like `debug_positions`.
+#[command]
+fn debug_anchor_positions(app_handle: tauri::AppHandle, offset_x: f64, offset_y: f64, unit: layout::OffsetUnit) -> Result<AnchorPreview, placement::PlacementError> {
+    let monitor = current_monitor(&app_handle).unwrap_or(layout::Monitor::new(layout::Rect::new(0.0, 0.0, 1920.0, 1080.0), 1.0));
+    let window = app_handle
+        .get_window("main")
+        .and_then(|window| logical_window_size(&window))
+        .unwrap_or(layout::Size::new(400.0, 300.0));
+    let area = monitor.logical_area();
+    
+    let positions = layout::Anchor::ALL
+        .iter()
+        .map(|&anchor| {
+            let request = placement::PlacementRequest::Anchor {
+                anchor,
+                offset_x,
+                offset_y,
+                unit,
+                monitor: settings::PreferredMonitor::Current,
+            };
+            let position = request.resolve_in(area, window, 0)?;
+            Ok((anchor, position.x, position.y))
+        })
+        .collect::<Result<Vec<_>, placement::PlacementError>>()?;
     
-    let window_size = (400.0, 300.0);
-    let margin = 50.0;
-    let available_width = screen_width - window_size.0 - (2.0 * margin);
-    let available_height = screen_height - window_size.1 - (2.0 * margin);
+    Ok(AnchorPreview {
+        area,
+        window,
+        positions,
+    })
+}
+
+/// Re-applies the window-level effects of settings that changed outside the
+/// frontend (e.g. an edited config file), which would otherwise need a restart.
+fn apply_window_settings(app_handle: &tauri::AppHandle, previous: &settings::Settings, current: &settings::Settings) {
+    if previous.show_on_all_spaces != current.show_on_all_spaces {
+        update_window_spaces(app_handle.clone(), current.show_on_all_spaces);
+    }
     
-    // Calculate all positions
-    for grid_pos in 0..(cols * rows) {
-        let row = grid_pos / cols;
-        let col = grid_pos % cols;
-        
-        let x = if cols == 1 {
-            margin + available_width / 2.0
-        } else {
-            margin + (col as f64 * available_width / (cols - 1) as f64)
-        };
-        
-        let y = if rows == 1 {
-            margin + available_height / 2.0
-        } else {
-            margin + (row as f64 * available_height / (rows - 1) as f64)
-        };
-        
-        positions.push((grid_pos, x, y));
-        println!("Position {}: ({}, {}) [row:{}, col:{}]", grid_pos, x, y, row, col);
+    // set_click_through persists the mode itself, so only a file edit needs applying here
+    let running_click_through = app_handle.state::<StateStore>().get().click_through;
+    if previous.click_through != current.click_through && running_click_through != current.click_through {
+        set_click_through(app_handle.clone(), current.click_through, app_handle.state());
     }
     
-    positions
+    let position_changed = previous.position_grid != current.position_grid
+        || previous.random_position != current.random_position
+        || previous.manual_position != current.manual_position
+        || previous.manual_x != current.manual_x
+        || previous.manual_y != current.manual_y
+        || previous.anchor_position != current.anchor_position
+        || previous.anchor != current.anchor
+        || previous.anchor_offset_x != current.anchor_offset_x
+        || previous.anchor_offset_y != current.anchor_offset_y
+        || previous.anchor_offset_unit != current.anchor_offset_unit
+        || previous.auto_detect_grid != current.auto_detect_grid
+        || previous.manual_grid_cols != current.manual_grid_cols
+        || previous.manual_grid_rows != current.manual_grid_rows
+        || previous.preferred_monitor != current.preferred_monitor;
+    
+    if position_changed {
+        reposition_main_window(app_handle, current);
+    }
+}
+
+/// Places the main window according to the position settings of `settings`.
+fn reposition_main_window(app_handle: &tauri::AppHandle, settings: &settings::Settings) {
+    if let Err(e) = set_window_position(app_handle.clone(), placement::PlacementRequest::from_settings(settings)) {
+        println!("⚠️ Failed to reposition main window: {}", e);
+    }
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -875,35 +756,18 @@ pub fn run() {
         }
     }
     
-    let quit = CustomMenuItem::new("quit".to_string(), "Quit");
-    let preferences = CustomMenuItem::new("preferences".to_string(), "Preferences");
-    let tray_menu = SystemTrayMenu::new()
-        .add_item(preferences)
-        .add_native_item(SystemTrayMenuItem::Separator)
-        .add_item(quit);
-
-    let system_tray = SystemTray::new().with_menu(tray_menu);
-
-    let app_state: AppState = Arc::new(Mutex::new(HashMap::new()));
+    // Profiles and topics are filled in once the settings store is loaded in setup
+    let system_tray = SystemTray::new().with_menu(tray::initial_menu());
+    let context = tauri::generate_context!();
 
     tauri::Builder::default()
-        .manage(app_state)
-        .invoke_handler(tauri::generate_handler![enable_temporary_icons, update_window_spaces, set_window_position, get_screen_info, save_manual_position, check_ollama_connection, test_drag, greet, fix_window_interactivity, quit_app, debug_positions, show_settings_window, show_main_window, initialize_window_position, resize_window_for_content, get_all_monitors_info])
+        .manage(StateStore::default())
+        .manage(timers::Timers::start())
+        .manage(tray::Tray::new(context.system_tray_icon().cloned()))
+        .invoke_handler(tauri::generate_handler![enable_temporary_icons, toggle_click_through, set_click_through, update_window_spaces, set_window_position, get_screen_info, save_manual_position, check_ollama_connection, test_drag, greet, fix_window_interactivity, quit_app, debug_positions, debug_anchor_positions, show_settings_window, show_main_window, initialize_window_position, resize_window_for_content, monitor_layout::get_all_monitors_info, position_rotation::card_advanced, snapping::drag_ended, hot_regions::set_hot_regions, app_state::get_app_state, app_state::set_current_card, app_state::set_provider_status, app_state::set_deck, shortcuts::get_shortcut_errors, shortcuts::parse_accelerator, shortcuts::check_keymap, shortcuts::rebind_shortcut, tray::set_topic_labels, settings::get_settings, settings::update_settings, settings::import_legacy_settings, settings::list_profiles, settings::create_profile, settings::clone_profile, settings::delete_profile, settings::switch_profile, monitor_layout::get_layout_bindings, monitor_layout::bind_layout_to_profile, monitor_layout::unbind_layout])
         .system_tray(system_tray)
         .on_system_tray_event(|app, event| match event {
-            SystemTrayEvent::MenuItemClick { id, .. } => match id.as_str() {
-                "quit" => {
-                    app.exit(0);
-                }
-                "preferences" => {
-                    if let Some(window) = app.get_window("settings") {
-                        let _ = window.show();
-                        let _ = window.set_focus();
-                        let _ = window.center();
-                    }
-                }
-                _ => {}
-            },
+            SystemTrayEvent::MenuItemClick { id, .. } => tray::on_menu_click(app, &id),
             _ => {}
         })
 .on_window_event(|event| match event.event() {
@@ -914,6 +778,20 @@ pub fn run() {
                     api.prevent_close();
                 }
             }
+            WindowEvent::Resized(_) if event.window().label() == "main" => {
+                // Anchored cards keep their anchored edge in place as they grow or shrink,
+                // unless dodge mode moved the card off its anchor
+                let app_handle = event.window().app_handle();
+                let settings = app_handle.state::<settings::SettingsStore>().get();
+                if settings.anchor_position && !settings.manual_position && !dodge::is_dodged(&app_handle) {
+                    reposition_main_window(&app_handle, &settings);
+                }
+                // Hot regions are clipped to the window, so the shape follows its size
+                hot_regions::refresh(&app_handle);
+            }
+            WindowEvent::Moved(_) if event.window().label() == "main" => {
+                snapping::window_moved(&event.window().app_handle());
+            }
             _ => {}
         })
 .setup(|app| {
@@ -928,18 +806,20 @@ pub fn run() {
                 }
             }
             
-            // Register shortcut for temporary icon access when in click-through mode
-            let app_handle = app.handle();
-            app.global_shortcut_manager()
-                .register("CmdOrCtrl+Shift+I", move || {
-                    println!("🔍 Global shortcut triggered: CmdOrCtrl+Shift+I (temporary icons)");
-                    let app_state: tauri::State<AppState> = app_handle.state();
-                    let _ = enable_temporary_icons(app_handle.clone(), app_state);
-                })
-                .expect("Failed to register temporary icons shortcut");
+            // Load persisted settings before any window asks for them
+            app.manage(settings::SettingsStore::load(&app.handle()));
+            app.manage(window_motion::WindowMotion::default());
+            app.manage(snapping::DragTracker::default());
+            app.manage(dodge::Dodged::default());
+            app.manage(hot_regions::HotRegions::default());
+            app.manage(position_rotation::PositionRotation::default());
+            app.manage(shortcuts::Shortcuts::default());
+            settings::watch(app.handle());
+            tray::refresh(&app.handle());
+            tray::update(&app.handle(), &app.state::<StateStore>().get());
             
-            println!("✅ Registered global shortcuts:");
-            println!("  - CmdOrCtrl+Shift+I (temporary icon access)");
+            // Register the global shortcuts; bindings taken by other apps are reported, not fatal
+            shortcuts::apply(&app.handle(), &app.state::<settings::SettingsStore>().get().shortcuts);
             
             if let Some(main_window) = app.get_window("main") {
                 // Set floating window level on macOS for non-interfering always-on-top behavior
@@ -972,8 +852,22 @@ pub fn run() {
             if let Some(settings_window) = app.get_window("settings") {
                 let _ = settings_window.hide();
             }
+            
+            // Restore persisted click-through mode once the main window is configured
+            if app.state::<settings::SettingsStore>().get().click_through {
+                set_click_through(app.handle(), true, app.state());
+            }
+            
+            // Switch profiles when monitors are docked, undocked or rearranged
+            monitor_layout::watch(app.handle());
+            
+            // Move the card around every few cards or minutes
+            position_rotation::schedule(&app.handle(), &app.state::<settings::SettingsStore>().get());
+            
+            // Keep the card off the pointer and the focused window when asked to
+            dodge::schedule(&app.handle(), &app.state::<settings::SettingsStore>().get());
             Ok(())
         })
-        .run(tauri::generate_context!())
+        .run(context)
         .expect("error while running tauri application");
 }
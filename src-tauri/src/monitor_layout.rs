@@ -0,0 +1,352 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::Duration;
+use tauri::Manager;
+
+use crate::layout::Rect;
+use crate::settings::{PreferredMonitor, SettingsStore};
+use crate::timers::Timers;
+use crate::work_area::WorkAreas;
+
+/// How often the connected monitors are compared against the last known
+/// layout. Tauri has no monitor hot-plug event, so this is polled.
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+const LAYOUT_TIMER: &str = "monitor-layout";
+
+/// The parts of a monitor that identify it and its place in an arrangement.
+/// Stored in settings as a monitor preference, so it is serde-friendly.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct MonitorDescriptor {
+    pub name: String,
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl MonitorDescriptor {
+    pub fn new(name: &str, x: i32, y: i32, width: u32, height: u32) -> Self {
+        MonitorDescriptor {
+            name: name.to_string(),
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    pub fn from_monitor(monitor: &tauri::Monitor) -> Self {
+        let position = monitor.position();
+        let size = monitor.size();
+        let name = monitor.name().map(String::as_str).unwrap_or_default();
+        MonitorDescriptor::new(name, position.x, position.y, size.width, size.height)
+    }
+}
+
+impl std::fmt::Display for MonitorDescriptor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}@{},{}:{}x{}", self.name, self.x, self.y, self.width, self.height)
+    }
+}
+
+/// Finds a remembered monitor among the connected ones: the same name and
+/// geometry first, then the same name (the arrangement or resolution
+/// changed), then the same geometry for monitors without a usable name.
+pub fn find_monitor(monitors: &[MonitorDescriptor], wanted: &MonitorDescriptor) -> Option<usize> {
+    let same_geometry = |m: &MonitorDescriptor| (m.x, m.y, m.width, m.height) == (wanted.x, wanted.y, wanted.width, wanted.height);
+
+    monitors
+        .iter()
+        .position(|m| m == wanted)
+        .or_else(|| {
+            if wanted.name.is_empty() {
+                None
+            } else {
+                monitors.iter().position(|m| m.name == wanted.name)
+            }
+        })
+        .or_else(|| monitors.iter().position(|m| (wanted.name.is_empty() || m.name.is_empty()) && same_geometry(m)))
+}
+
+/// Picks the monitor for `preference` among `monitors`. `primary` and
+/// `current` are indices into `monitors` when known. When the preferred
+/// monitor is not connected this falls back to the primary monitor, then
+/// the current one, then the first; `None` only when there are no monitors.
+pub fn resolve_preference(
+    monitors: &[MonitorDescriptor],
+    primary: Option<usize>,
+    current: Option<usize>,
+    preference: &PreferredMonitor,
+) -> Option<usize> {
+    let preferred = match preference {
+        PreferredMonitor::Primary => primary,
+        PreferredMonitor::Auto | PreferredMonitor::Current => current,
+        PreferredMonitor::Index(index) => Some(*index).filter(|index| *index < monitors.len()),
+        PreferredMonitor::Monitor(monitor) => find_monitor(monitors, monitor),
+    };
+
+    preferred
+        .or(primary)
+        .or(current)
+        .or(if monitors.is_empty() { None } else { Some(0) })
+}
+
+/// Everything the settings UI shows about a connected monitor.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct MonitorInfo {
+    pub index: usize,
+    /// What to store as the monitor preference to pick this monitor
+    pub id: MonitorDescriptor,
+    pub scale_factor: f64,
+    /// The part of the monitor not covered by panels and docks, in physical pixels
+    pub work_area: Rect,
+    pub is_primary: bool,
+    pub is_current: bool,
+}
+
+/// Builds a stable, human-readable fingerprint of a monitor arrangement,
+/// e.g. `DP-1@0,0:2560x1440|eDP-1@2560,0:1920x1080`. Monitors are sorted by
+/// position so the order the OS enumerates them in does not matter.
+pub fn fingerprint(monitors: &[MonitorDescriptor]) -> String {
+    let mut sorted: Vec<&MonitorDescriptor> = monitors.iter().collect();
+    sorted.sort_by(|a, b| (a.x, a.y, &a.name).cmp(&(b.x, b.y, &b.name)));
+    sorted
+        .iter()
+        .map(|m| m.to_string())
+        .collect::<Vec<_>>()
+        .join("|")
+}
+
+/// Fingerprint of the monitors connected right now, or `None` when they
+/// cannot be queried (e.g. before the main window exists).
+pub fn current_fingerprint(app_handle: &tauri::AppHandle) -> Option<String> {
+    let main_window = app_handle.get_window("main")?;
+    let monitors = main_window.available_monitors().ok()?;
+    if monitors.is_empty() {
+        return None;
+    }
+    let descriptors: Vec<MonitorDescriptor> = monitors.iter().map(MonitorDescriptor::from_monitor).collect();
+    Some(fingerprint(&descriptors))
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LayoutBindingsInfo {
+    /// Fingerprint of the currently connected monitors
+    pub current: Option<String>,
+    pub bindings: BTreeMap<String, String>,
+}
+
+/// Switches to the profile bound to `fingerprint`, if any, and re-applies
+/// the window position so the card lands on a monitor that still exists.
+fn apply_layout(app_handle: &tauri::AppHandle, fingerprint: &str) {
+    let store = app_handle.state::<SettingsStore>();
+    let bound_profile = store.with_profiles(|profiles| profiles.profile_for_layout(fingerprint).map(str::to_string));
+
+    if let Some(profile) = bound_profile {
+        if store.profiles().active != profile {
+            println!("🖥️ Monitor layout matches profile \"{}\", switching", profile);
+            if let Err(e) = store.modify_profiles(app_handle, |profiles| profiles.switch(&profile)) {
+                println!("⚠️ Failed to switch to profile \"{}\": {}", profile, e);
+            }
+            return;
+        }
+    }
+
+    // Same profile, different monitors: place the card again within the new layout
+    crate::reposition_main_window(app_handle, &store.get());
+}
+
+/// Polls the monitor layout and reacts to docking, undocking and other
+/// arrangement changes. Also applies a binding for the layout at startup.
+pub fn watch(app_handle: tauri::AppHandle) {
+    let timers = app_handle.state::<Timers>();
+    let app = app_handle.clone();
+    timers.once(LAYOUT_TIMER, Duration::ZERO, move || {
+        let last = current_fingerprint(&app);
+        if let Some(fingerprint) = &last {
+            println!("🖥️ Monitor layout: {}", fingerprint);
+            let store = app.state::<SettingsStore>();
+            if store.with_profiles(|profiles| profiles.profile_for_layout(fingerprint).is_some()) {
+                spawn_apply_layout(&app, fingerprint.clone());
+            }
+        }
+        poll(app, last);
+    });
+}
+
+/// Checks the layout every `POLL_INTERVAL`, starting from `last`.
+fn poll(app_handle: tauri::AppHandle, mut last: Option<String>) {
+    let timers = app_handle.state::<Timers>();
+    let app = app_handle.clone();
+    timers.repeat(LAYOUT_TIMER, POLL_INTERVAL, move || {
+        let current = current_fingerprint(&app);
+        if current.is_none() || current == last {
+            return;
+        }
+
+        let fingerprint = current.clone().unwrap_or_default();
+        println!("🖥️ Monitor layout changed: {}", fingerprint);
+        let _ = app.emit_all("monitor-layout-changed", &fingerprint);
+        spawn_apply_layout(&app, fingerprint);
+        last = current;
+    });
+}
+
+/// Switching profiles re-registers shortcuts, which waits on the main
+/// thread, so it is kept off the timer thread.
+fn spawn_apply_layout(app_handle: &tauri::AppHandle, fingerprint: String) {
+    let app_handle = app_handle.clone();
+    std::thread::spawn(move || apply_layout(&app_handle, &fingerprint));
+}
+
+/// Describes every connected monitor. The primary monitor comes from the
+/// OS rather than being assumed to be the first one listed.
+#[tauri::command]
+pub fn get_all_monitors_info(app_handle: tauri::AppHandle) -> Vec<MonitorInfo> {
+    // Any window can list the monitors, e.g. the settings window asking for them
+    let Some(window) = app_handle.get_window("main").or_else(|| app_handle.windows().into_values().next()) else {
+        return vec![fallback_monitor_info()];
+    };
+    let monitors = window.available_monitors().unwrap_or_default();
+    if monitors.is_empty() {
+        return vec![fallback_monitor_info()];
+    }
+    let descriptors: Vec<MonitorDescriptor> = monitors.iter().map(MonitorDescriptor::from_monitor).collect();
+    let primary = window
+        .primary_monitor()
+        .ok()
+        .flatten()
+        .and_then(|monitor| find_monitor(&descriptors, &MonitorDescriptor::from_monitor(&monitor)));
+    let current = window
+        .current_monitor()
+        .ok()
+        .flatten()
+        .and_then(|monitor| find_monitor(&descriptors, &MonitorDescriptor::from_monitor(&monitor)));
+    let work_areas = WorkAreas::current();
+
+    monitors
+        .iter()
+        .zip(descriptors)
+        .enumerate()
+        .map(|(index, (monitor, id))| MonitorInfo {
+            index,
+            id,
+            scale_factor: monitor.scale_factor(),
+            work_area: crate::layout_monitor(monitor, &work_areas).work_area,
+            is_primary: primary == Some(index),
+            is_current: current == Some(index),
+        })
+        .collect()
+}
+
+/// A 1920x1080 monitor, listed when none can be detected so the monitor
+/// picker is never empty.
+fn fallback_monitor_info() -> MonitorInfo {
+    MonitorInfo {
+        index: 0,
+        id: MonitorDescriptor::new("", 0, 0, 1920, 1080),
+        scale_factor: 1.0,
+        work_area: Rect::new(0.0, 0.0, 1920.0, 1080.0),
+        is_primary: true,
+        is_current: true,
+    }
+}
+
+#[tauri::command]
+pub fn get_layout_bindings(app_handle: tauri::AppHandle, store: tauri::State<SettingsStore>) -> LayoutBindingsInfo {
+    LayoutBindingsInfo {
+        current: current_fingerprint(&app_handle),
+        bindings: store.with_profiles(|profiles| profiles.layout_bindings().clone()),
+    }
+}
+
+/// Binds `profile` to a monitor layout; defaults to the layout connected now.
+#[tauri::command]
+pub fn bind_layout_to_profile(app_handle: tauri::AppHandle, store: tauri::State<SettingsStore>, profile: String, fingerprint: Option<String>) -> Result<String, String> {
+    let fingerprint = fingerprint
+        .or_else(|| current_fingerprint(&app_handle))
+        .ok_or_else(|| "Could not detect the current monitor layout".to_string())?;
+
+    println!("🖥️ Binding layout {} to profile \"{}\"", fingerprint, profile);
+    store.modify_profiles(&app_handle, |profiles| profiles.bind_layout(&fingerprint, &profile))?;
+    Ok(fingerprint)
+}
+
+#[tauri::command]
+pub fn unbind_layout(app_handle: tauri::AppHandle, store: tauri::State<SettingsStore>, fingerprint: String) -> Result<(), String> {
+    println!("🖥️ Unbinding layout {}", fingerprint);
+    store.modify_profiles(&app_handle, |profiles| profiles.unbind_layout(&fingerprint))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monitor(name: &str, x: i32, y: i32, width: u32, height: u32) -> MonitorDescriptor {
+        MonitorDescriptor::new(name, x, y, width, height)
+    }
+
+    #[test]
+    fn find_monitor_prefers_exact_then_name_then_geometry() {
+        let layout = [monitor("eDP-1", 0, 0, 1920, 1080), monitor("DP-1", 1920, 0, 2560, 1440)];
+        assert_eq!(find_monitor(&layout, &monitor("DP-1", 1920, 0, 2560, 1440)), Some(1));
+        // Moved to the other side of the laptop
+        assert_eq!(find_monitor(&layout, &monitor("DP-1", -2560, 0, 2560, 1440)), Some(1));
+        // Unnamed monitors are matched by where they are
+        assert_eq!(find_monitor(&[monitor("", 0, 0, 1920, 1080)], &monitor("", 0, 0, 1920, 1080)), Some(0));
+        assert_eq!(find_monitor(&layout, &monitor("HDMI-1", 1920, 0, 2560, 1440)), None);
+    }
+
+    #[test]
+    fn resolve_preference_survives_reordered_monitors() {
+        let wanted = PreferredMonitor::Monitor(monitor("DP-1", 1920, 0, 2560, 1440));
+        let docked = [monitor("eDP-1", 0, 0, 1920, 1080), monitor("DP-1", 1920, 0, 2560, 1440)];
+        let reordered = [docked[1].clone(), docked[0].clone()];
+        assert_eq!(resolve_preference(&docked, Some(0), Some(0), &wanted), Some(1));
+        assert_eq!(resolve_preference(&reordered, Some(1), Some(1), &wanted), Some(0));
+    }
+
+    #[test]
+    fn resolve_preference_falls_back_when_monitor_disappears() {
+        let undocked = [monitor("eDP-1", 0, 0, 1920, 1080), monitor("HDMI-1", 1920, 0, 1920, 1080)];
+        let gone = PreferredMonitor::Monitor(monitor("DP-1", 1920, 0, 2560, 1440));
+        assert_eq!(resolve_preference(&undocked, Some(0), Some(1), &gone), Some(0));
+        assert_eq!(resolve_preference(&undocked, None, Some(1), &gone), Some(1));
+        assert_eq!(resolve_preference(&undocked, None, None, &PreferredMonitor::Index(5)), Some(0));
+        assert_eq!(resolve_preference(&[], None, None, &PreferredMonitor::Primary), None);
+    }
+
+    #[test]
+    fn resolve_preference_uses_reported_primary() {
+        let layout = [monitor("DP-1", -2560, 0, 2560, 1440), monitor("eDP-1", 0, 0, 1920, 1080)];
+        assert_eq!(resolve_preference(&layout, Some(1), Some(0), &PreferredMonitor::Primary), Some(1));
+        assert_eq!(resolve_preference(&layout, Some(1), Some(0), &PreferredMonitor::Auto), Some(0));
+    }
+
+    #[test]
+    fn fingerprint_ignores_enumeration_order() {
+        let laptop = monitor("eDP-1", 2560, 0, 1920, 1080);
+        let external = monitor("DP-1", 0, 0, 2560, 1440);
+        assert_eq!(
+            fingerprint(&[laptop.clone(), external.clone()]),
+            fingerprint(&[external, laptop])
+        );
+    }
+
+    #[test]
+    fn fingerprint_describes_each_monitor() {
+        let layout = [monitor("DP-1", 0, 0, 2560, 1440), monitor("eDP-1", 2560, 0, 1920, 1080)];
+        assert_eq!(fingerprint(&layout), "DP-1@0,0:2560x1440|eDP-1@2560,0:1920x1080");
+    }
+
+    #[test]
+    fn fingerprint_changes_with_arrangement() {
+        let side_by_side = [monitor("DP-1", 0, 0, 2560, 1440), monitor("eDP-1", 2560, 0, 1920, 1080)];
+        let stacked = [monitor("DP-1", 0, 0, 2560, 1440), monitor("eDP-1", 0, 1440, 1920, 1080)];
+        let undocked = [monitor("eDP-1", 0, 0, 1920, 1080)];
+        assert_ne!(fingerprint(&side_by_side), fingerprint(&stacked));
+        assert_ne!(fingerprint(&side_by_side), fingerprint(&undocked));
+    }
+}
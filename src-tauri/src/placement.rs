@@ -0,0 +1,425 @@
+use serde::{Deserialize, Serialize};
+
+use crate::layout::{self, Anchor, Grid, Monitor, OffsetUnit, Point, Rect, Size};
+use crate::settings::{PreferredMonitor, Settings};
+
+/// Largest grid the settings UI offers; anything bigger is a bad request.
+const MAX_GRID_DIMENSION: i32 = 12;
+
+/// Where the main window should go. Sent by the frontend as
+/// `{ "mode": "grid", "slot": 3, ... }` and validated before use.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+#[serde(tag = "mode", rename_all = "camelCase")]
+pub enum PlacementRequest {
+    /// A slot in an evenly spaced grid over the monitor
+    #[serde(rename_all = "camelCase")]
+    Grid {
+        slot: i32,
+        #[serde(default = "default_true")]
+        auto_detect_grid: bool,
+        #[serde(default = "default_cols")]
+        cols: i32,
+        #[serde(default = "default_rows")]
+        rows: i32,
+        #[serde(default)]
+        monitor: PreferredMonitor,
+    },
+    /// A random spot on the monitor
+    #[serde(rename_all = "camelCase")]
+    Random {
+        #[serde(default)]
+        monitor: PreferredMonitor,
+    },
+    /// An absolute screen position, e.g. where the user dragged the card
+    #[serde(rename_all = "camelCase")]
+    Manual { x: f64, y: f64 },
+    /// Pinned to a corner, edge or the centre of the monitor. Unlike grid
+    /// slots this means the same thing on every screen size and keeps the
+    /// card's edge in place when it is resized.
+    #[serde(rename_all = "camelCase")]
+    Anchor {
+        anchor: Anchor,
+        #[serde(default)]
+        offset_x: f64,
+        #[serde(default)]
+        offset_y: f64,
+        #[serde(default)]
+        unit: OffsetUnit,
+        #[serde(default)]
+        monitor: PreferredMonitor,
+    },
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_cols() -> i32 {
+    4
+}
+
+fn default_rows() -> i32 {
+    3
+}
+
+/// Why a placement request was refused. Serialized for the frontend as
+/// `{ "kind": "invalidGrid", "message": "..." }`.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum PlacementError {
+    InvalidGrid { message: String },
+    InvalidSlot { message: String },
+    InvalidCoordinate { message: String },
+    WindowUnavailable { message: String },
+}
+
+impl PlacementError {
+    fn invalid_grid(cols: i32, rows: i32) -> Self {
+        PlacementError::InvalidGrid {
+            message: format!("Grid must be between 1x1 and {0}x{0}, got {1}x{2}", MAX_GRID_DIMENSION, cols, rows),
+        }
+    }
+
+    fn invalid_coordinate(name: &str, value: f64) -> Self {
+        PlacementError::InvalidCoordinate {
+            message: format!("{} must be a finite number, got {}", name, value),
+        }
+    }
+
+    pub fn window_unavailable(message: impl Into<String>) -> Self {
+        PlacementError::WindowUnavailable { message: message.into() }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            PlacementError::InvalidGrid { message }
+            | PlacementError::InvalidSlot { message }
+            | PlacementError::InvalidCoordinate { message }
+            | PlacementError::WindowUnavailable { message } => message,
+        }
+    }
+}
+
+impl std::fmt::Display for PlacementError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message())
+    }
+}
+
+impl PlacementRequest {
+    /// The placement described by the position fields of `settings`.
+    pub fn from_settings(settings: &Settings) -> Self {
+        if settings.manual_position {
+            PlacementRequest::Manual {
+                x: settings.manual_x,
+                y: settings.manual_y,
+            }
+        } else if settings.anchor_position {
+            PlacementRequest::Anchor {
+                anchor: settings.anchor,
+                offset_x: settings.anchor_offset_x,
+                offset_y: settings.anchor_offset_y,
+                unit: settings.anchor_offset_unit,
+                monitor: settings.preferred_monitor.clone(),
+            }
+        } else if settings.random_position {
+            PlacementRequest::Random {
+                monitor: settings.preferred_monitor.clone(),
+            }
+        } else {
+            PlacementRequest::Grid {
+                slot: settings.position_grid,
+                auto_detect_grid: settings.auto_detect_grid,
+                cols: settings.manual_grid_cols,
+                rows: settings.manual_grid_rows,
+                monitor: settings.preferred_monitor.clone(),
+            }
+        }
+    }
+
+    /// The monitor preference the request should be resolved against.
+    /// Manual positions are absolute and ignore it.
+    pub fn monitor(&self) -> PreferredMonitor {
+        match self {
+            PlacementRequest::Grid { monitor, .. }
+            | PlacementRequest::Random { monitor }
+            | PlacementRequest::Anchor { monitor, .. } => monitor.clone(),
+            PlacementRequest::Manual { .. } => PreferredMonitor::Current,
+        }
+    }
+
+    /// Checks the request without touching any window.
+    pub fn validate(&self) -> Result<(), PlacementError> {
+        match self {
+            PlacementRequest::Grid {
+                slot,
+                auto_detect_grid,
+                cols,
+                rows,
+                ..
+            } => {
+                if *slot < 0 {
+                    return Err(PlacementError::InvalidSlot {
+                        message: format!("Grid slot cannot be negative, got {}", slot),
+                    });
+                }
+                if !auto_detect_grid && (Grid::new(*cols, *rows).is_none() || *cols > MAX_GRID_DIMENSION || *rows > MAX_GRID_DIMENSION) {
+                    return Err(PlacementError::invalid_grid(*cols, *rows));
+                }
+                Ok(())
+            }
+            PlacementRequest::Random { .. } => Ok(()),
+            PlacementRequest::Manual { x, y } => {
+                finite("x", *x)?;
+                finite("y", *y)
+            }
+            PlacementRequest::Anchor { offset_x, offset_y, .. } => {
+                finite("offsetX", *offset_x)?;
+                finite("offsetY", *offset_y)
+            }
+        }
+    }
+
+    /// Validates the request and computes where to put a window of logical
+    /// size `window` on `monitor`. Grid, random and anchor placement happen in
+    /// the monitor's logical pixels, so margins and offsets look the same on
+    /// every display; the result is in global physical pixels. Manual
+    /// positions are already physical and pass through unchanged. `seed`
+    /// only matters for random placement.
+    pub fn resolve(&self, monitor: &Monitor, window: Size, seed: u64) -> Result<Point, PlacementError> {
+        match self {
+            PlacementRequest::Manual { x, y } => {
+                self.validate()?;
+                Ok(Point::new(*x, *y))
+            }
+            _ => Ok(monitor.to_global(self.resolve_in(monitor.logical_area(), window, seed)?)),
+        }
+    }
+
+    /// Like `resolve`, but within a plain logical `area` and without mapping
+    /// the result to physical pixels. Used for previews.
+    pub fn resolve_in(&self, area: Rect, window: Size, seed: u64) -> Result<Point, PlacementError> {
+        self.validate()?;
+
+        let position = match self {
+            PlacementRequest::Grid {
+                slot,
+                auto_detect_grid,
+                cols,
+                rows,
+                ..
+            } => {
+                let grid = layout::grid_dimensions(area.size(), *auto_detect_grid, *cols, *rows);
+                layout::grid_slot_position(area, window, grid, *slot as u32)
+            }
+            PlacementRequest::Random { .. } => layout::random_position(area, window, seed),
+            PlacementRequest::Manual { x, y } => Point::new(*x, *y),
+            PlacementRequest::Anchor {
+                anchor,
+                offset_x,
+                offset_y,
+                unit,
+                ..
+            } => {
+                let offset = Point::new(unit.to_pixels(*offset_x, area.width), unit.to_pixels(*offset_y, area.height));
+                layout::anchor_position(area, window, *anchor, offset)
+            }
+        };
+        Ok(position)
+    }
+}
+
+fn finite(name: &str, value: f64) -> Result<(), PlacementError> {
+    if value.is_finite() {
+        Ok(())
+    } else {
+        Err(PlacementError::invalid_coordinate(name, value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const FULL_HD: Monitor = Monitor {
+        bounds: Rect { x: 0.0, y: 0.0, width: 1920.0, height: 1080.0 },
+        work_area: Rect { x: 0.0, y: 0.0, width: 1920.0, height: 1080.0 },
+        scale_factor: 1.0,
+    };
+    const CARD: Size = Size { width: 400.0, height: 300.0 };
+
+    fn parse(value: serde_json::Value) -> PlacementRequest {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn requests_deserialize_from_tagged_json() {
+        assert_eq!(
+            parse(json!({ "mode": "grid", "slot": 2, "autoDetectGrid": false, "cols": 3, "rows": 2, "monitor": "1" })),
+            PlacementRequest::Grid {
+                slot: 2,
+                auto_detect_grid: false,
+                cols: 3,
+                rows: 2,
+                monitor: PreferredMonitor::Index(1),
+            }
+        );
+        assert_eq!(parse(json!({ "mode": "random" })), PlacementRequest::Random { monitor: PreferredMonitor::Auto });
+        assert_eq!(
+            parse(json!({ "mode": "anchor", "anchor": "bottom-right", "offsetX": 24 })),
+            PlacementRequest::Anchor {
+                anchor: Anchor::BottomRight,
+                offset_x: 24.0,
+                offset_y: 0.0,
+                unit: OffsetUnit::Px,
+                monitor: PreferredMonitor::Auto,
+            }
+        );
+        assert!(serde_json::from_value::<PlacementRequest>(json!({ "mode": "teleport" })).is_err());
+    }
+
+    #[test]
+    fn empty_manual_grid_is_an_error_not_a_panic() {
+        let request = parse(json!({ "mode": "grid", "slot": 5, "autoDetectGrid": false, "cols": 0, "rows": 3 }));
+        assert!(matches!(request.resolve(&FULL_HD, CARD, 0), Err(PlacementError::InvalidGrid { .. })));
+
+        let oversized = parse(json!({ "mode": "grid", "slot": 0, "autoDetectGrid": false, "cols": 40, "rows": 3 }));
+        assert!(matches!(oversized.validate(), Err(PlacementError::InvalidGrid { .. })));
+    }
+
+    #[test]
+    fn manual_grid_is_ignored_when_auto_detecting() {
+        let request = parse(json!({ "mode": "grid", "slot": 0, "cols": 0, "rows": 0 }));
+        assert_eq!(request.resolve(&FULL_HD, CARD, 0), Ok(Point::new(50.0, 50.0)));
+    }
+
+    #[test]
+    fn negative_slots_are_rejected() {
+        let request = parse(json!({ "mode": "grid", "slot": -1 }));
+        assert!(matches!(request.validate(), Err(PlacementError::InvalidSlot { .. })));
+    }
+
+    #[test]
+    fn non_finite_coordinates_are_rejected() {
+        let manual = PlacementRequest::Manual { x: f64::NAN, y: 0.0 };
+        assert!(matches!(manual.validate(), Err(PlacementError::InvalidCoordinate { .. })));
+        let anchor = PlacementRequest::Anchor {
+            anchor: Anchor::Top,
+            offset_x: 0.0,
+            offset_y: f64::INFINITY,
+            unit: OffsetUnit::Px,
+            monitor: PreferredMonitor::Auto,
+        };
+        assert!(matches!(anchor.validate(), Err(PlacementError::InvalidCoordinate { .. })));
+    }
+
+    #[test]
+    fn random_placement_on_a_tiny_monitor_does_not_underflow() {
+        let tiny = Monitor::new(Rect::new(0.0, 0.0, 320.0, 240.0), 1.0);
+        let request = PlacementRequest::Random { monitor: PreferredMonitor::Auto };
+        assert_eq!(request.resolve(&tiny, CARD, u64::MAX), Ok(Point::new(0.0, 0.0)));
+    }
+
+    #[test]
+    fn grid_margins_are_logical_on_hidpi_monitors() {
+        // 2x monitor to the right of a 1x monitor
+        let retina = Monitor::new(Rect::new(1920.0, 0.0, 2880.0, 1800.0), 2.0);
+        let request = parse(json!({ "mode": "grid", "slot": 0 }));
+        assert_eq!(request.resolve(&retina, CARD, 0), Ok(Point::new(1920.0 + 100.0, 100.0)));
+
+        // The last slot keeps the full (scaled) window on screen
+        let last = parse(json!({ "mode": "grid", "slot": 11, "autoDetectGrid": false, "cols": 4, "rows": 3 }));
+        let p = last.resolve(&retina, CARD, 0).unwrap();
+        assert_eq!(p, Point::new(1920.0 + 2880.0 - 800.0 - 100.0, 1800.0 - 600.0 - 100.0));
+    }
+
+    #[test]
+    fn same_request_lands_in_the_same_relative_spot_on_mixed_dpi_monitors() {
+        let standard = Monitor::new(Rect::new(0.0, 0.0, 1440.0, 900.0), 1.0);
+        let retina = Monitor::new(Rect::new(1440.0, 0.0, 2880.0, 1800.0), 2.0);
+        let request = PlacementRequest::Anchor {
+            anchor: Anchor::BottomRight,
+            offset_x: 24.0,
+            offset_y: 24.0,
+            unit: OffsetUnit::Px,
+            monitor: PreferredMonitor::Auto,
+        };
+
+        let on_standard = request.resolve(&standard, CARD, 0).unwrap();
+        let on_retina = request.resolve(&retina, CARD, 0).unwrap();
+        assert_eq!(on_standard, Point::new(1440.0 - 400.0 - 24.0, 900.0 - 300.0 - 24.0));
+        assert_eq!(on_retina, Point::new(1440.0 + 2880.0 - 800.0 - 48.0, 1800.0 - 600.0 - 48.0));
+    }
+
+    #[test]
+    fn fractional_scale_factors_keep_the_window_on_screen() {
+        let monitor = Monitor::new(Rect::new(-1536.0, 0.0, 1536.0, 864.0), 1.25);
+        for slot in 0..12 {
+            let request = parse(json!({ "mode": "grid", "slot": slot }));
+            let p = request.resolve(&monitor, CARD, 0).unwrap();
+            let size = monitor.to_physical(CARD);
+            assert!(p.x >= -1536.0 && p.x + size.width <= 0.0 + 1e-6);
+            assert!(p.y >= 0.0 && p.y + size.height <= 864.0 + 1e-6);
+        }
+    }
+
+    #[test]
+    fn grid_slots_avoid_panels_and_docks() {
+        // 28px top bar and a 70px dock at the bottom
+        let monitor = FULL_HD.with_work_area(Rect::new(0.0, 28.0, 1920.0, 982.0));
+        let first = parse(json!({ "mode": "grid", "slot": 0 })).resolve(&monitor, CARD, 0).unwrap();
+        let last = parse(json!({ "mode": "grid", "slot": 11 })).resolve(&monitor, CARD, 0).unwrap();
+        assert_eq!(first, Point::new(50.0, 78.0));
+        assert_eq!(last.y + CARD.height, 1010.0 - 50.0);
+    }
+
+    #[test]
+    fn percent_anchor_offsets_survive_resolution_changes() {
+        let request = parse(json!({ "mode": "anchor", "anchor": "top-left", "offsetX": 10, "offsetY": 10, "unit": "percent" }));
+        let small = Monitor::new(Rect::new(0.0, 0.0, 1280.0, 720.0), 1.0);
+        let large = Monitor::new(Rect::new(0.0, 0.0, 2560.0, 1440.0), 1.0);
+        assert_eq!(request.resolve(&small, CARD, 0), Ok(Point::new(128.0, 72.0)));
+        assert_eq!(request.resolve(&large, CARD, 0), Ok(Point::new(256.0, 144.0)));
+    }
+
+    #[test]
+    fn anchored_edge_stays_put_when_the_card_grows() {
+        let request = parse(json!({ "mode": "anchor", "anchor": "bottom-right", "offsetX": 24, "offsetY": 24 }));
+        let small = request.resolve(&FULL_HD, CARD, 0).unwrap();
+        let grown = request.resolve(&FULL_HD, Size::new(600.0, 400.0), 0).unwrap();
+        assert_eq!(small.x + CARD.width, grown.x + 600.0);
+        assert_eq!(small.y + CARD.height, grown.y + 400.0);
+    }
+
+    #[test]
+    fn manual_positions_are_not_scaled() {
+        let retina = Monitor::new(Rect::new(0.0, 0.0, 2880.0, 1800.0), 2.0);
+        let request = PlacementRequest::Manual { x: 300.0, y: 200.0 };
+        assert_eq!(request.resolve(&retina, CARD, 0), Ok(Point::new(300.0, 200.0)));
+    }
+
+    #[test]
+    fn errors_serialize_with_kind_and_message() {
+        let error = PlacementError::invalid_grid(0, 3);
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            json!({ "kind": "invalidGrid", "message": "Grid must be between 1x1 and 12x12, got 0x3" })
+        );
+    }
+
+    #[test]
+    fn from_settings_prefers_manual_then_anchor_then_random_then_grid() {
+        let mut settings = Settings {
+            position_grid: 4,
+            ..Settings::default()
+        };
+        assert!(matches!(PlacementRequest::from_settings(&settings), PlacementRequest::Grid { slot: 4, .. }));
+        settings.random_position = true;
+        assert!(matches!(PlacementRequest::from_settings(&settings), PlacementRequest::Random { .. }));
+        settings.anchor_position = true;
+        assert!(matches!(PlacementRequest::from_settings(&settings), PlacementRequest::Anchor { .. }));
+        settings.manual_position = true;
+        assert!(matches!(PlacementRequest::from_settings(&settings), PlacementRequest::Manual { .. }));
+    }
+}
@@ -0,0 +1,251 @@
+//! Moves the card to a new spot every few cards or minutes, so it does not
+//! fade into the background after sitting in the same place for days.
+//!
+//! Rotation follows the placement mode: grid placement moves to another
+//! slot, random placement picks a new random position. Anchored and dragged
+//! cards stay where the user put them.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::Manager;
+
+use crate::app_state::StateStore;
+use crate::layout;
+use crate::placement::PlacementRequest;
+use crate::settings::{RotationTrigger, Settings, SettingsStore};
+use crate::timers::Timers;
+use crate::window_motion::{Motion, WindowMotion};
+
+const ROTATION_TIMER: &str = "position-rotation";
+
+/// Picks the next grid slot: never the current one or an excluded one, and
+/// preferably none of the `recent` ones. Recent slots are only reused when
+/// nothing else is left. Returns `None` when every other slot is excluded.
+pub fn next_slot(slot_count: i32, current: i32, recent: &[i32], excluded: &[i32], seed: u64) -> Option<i32> {
+    let allowed: Vec<i32> = (0..slot_count).filter(|slot| *slot != current && !excluded.contains(slot)).collect();
+    let fresh: Vec<i32> = allowed.iter().copied().filter(|slot| !recent.contains(slot)).collect();
+    let candidates = if fresh.is_empty() { allowed } else { fresh };
+    if candidates.is_empty() {
+        return None;
+    }
+    Some(candidates[(seed % candidates.len() as u64) as usize])
+}
+
+/// Rotation progress, managed as Tauri state.
+pub struct PositionRotation {
+    state: Mutex<RotationState>,
+}
+
+struct RotationState {
+    /// Slot the card was last rotated to, if rotation has moved it
+    current: Option<i32>,
+    /// Most recent slots, newest last
+    recent: VecDeque<i32>,
+    cards: u32,
+}
+
+impl Default for PositionRotation {
+    fn default() -> Self {
+        PositionRotation {
+            state: Mutex::new(RotationState {
+                current: None,
+                recent: VecDeque::new(),
+                cards: 0,
+            }),
+        }
+    }
+}
+
+impl PositionRotation {
+    /// Forgets progress towards the next rotation, e.g. while rotation is off.
+    fn reset(&self, forget_slot: bool) {
+        let mut state = crate::lock(&self.state);
+        state.cards = 0;
+        if forget_slot {
+            state.current = None;
+            state.recent.clear();
+        }
+    }
+
+    /// Picks the slot to rotate to and records it in the history.
+    fn advance_slot(&self, settings: &Settings, slot_count: i32, seed: u64) -> Option<i32> {
+        let mut state = crate::lock(&self.state);
+        let current = state.current.unwrap_or(settings.position_grid);
+        let avoid = settings.rotation_avoid_recent as usize;
+        while state.recent.len() > avoid {
+            state.recent.pop_front();
+        }
+
+        let recent: Vec<i32> = state.recent.iter().copied().collect();
+        let slot = next_slot(slot_count, current, &recent, &settings.rotation_excluded_slots, seed)?;
+        state.current = Some(slot);
+        if avoid > 0 {
+            state.recent.push_back(current);
+            if state.recent.len() > avoid {
+                state.recent.pop_front();
+            }
+        }
+        Some(slot)
+    }
+}
+
+/// Whether rotation applies to the placement mode in `settings`.
+fn rotation_active(settings: &Settings) -> bool {
+    settings.position_rotation && !settings.manual_position && !settings.anchor_position
+}
+
+/// Arms or cancels the timer behind minute-based rotation to match
+/// `settings`. Progress restarts whenever rotation is off, so switching it on
+/// never moves the card straight away; a running timer is only re-armed when
+/// its interval changes.
+pub fn schedule(app_handle: &tauri::AppHandle, settings: &Settings) {
+    let timers = app_handle.state::<Timers>();
+    let rotation = app_handle.state::<PositionRotation>();
+    if !rotation_active(settings) || settings.rotation_trigger != RotationTrigger::Minutes {
+        timers.cancel(ROTATION_TIMER);
+        rotation.reset(!rotation_active(settings));
+        return;
+    }
+
+    let interval = Duration::from_secs(u64::from(settings.rotation_interval) * 60);
+    if timers.period(ROTATION_TIMER) == Some(interval) {
+        return;
+    }
+    rotation.reset(false);
+    let app_handle = app_handle.clone();
+    timers.repeat(ROTATION_TIMER, interval, move || {
+        if app_handle.state::<StateStore>().get().paused {
+            return;
+        }
+        let settings = app_handle.state::<SettingsStore>().get();
+        rotate(&app_handle, &settings);
+    });
+}
+
+/// Counts a card shown on the main window towards card-based rotation.
+#[tauri::command]
+pub fn card_advanced(app_handle: tauri::AppHandle, rotation: tauri::State<PositionRotation>, store: tauri::State<SettingsStore>) {
+    let settings = store.get();
+    if !rotation_active(&settings) || settings.rotation_trigger != RotationTrigger::Cards {
+        return;
+    }
+
+    let due = {
+        let mut state = crate::lock(&rotation.state);
+        state.cards += 1;
+        state.cards >= settings.rotation_interval
+    };
+    if due {
+        rotation.reset(false);
+        rotate(&app_handle, &settings);
+    }
+}
+
+/// Moves the main window to its next rotation position.
+fn rotate(app_handle: &tauri::AppHandle, settings: &Settings) {
+    let request = match rotation_request(app_handle, settings) {
+        Some(request) => request,
+        None => {
+            println!("🔄 No free grid slot to rotate to");
+            return;
+        }
+    };
+
+    let (main_window, target) = match crate::resolve_main_window_position(app_handle, &request) {
+        Ok(resolved) => resolved,
+        Err(e) => {
+            println!("⚠️ Failed to rotate main window: {}", e);
+            return;
+        }
+    };
+    println!("🔄 Rotating main window: {:?}", request);
+
+    // Rotation slides even when other moves are instant, unless turned off
+    let motion = if settings.rotation_animate {
+        Motion {
+            duration: Duration::from_millis(u64::from(settings.movement_duration_ms)),
+            easing: settings.movement_easing,
+        }
+    } else {
+        Motion::INSTANT
+    };
+    crate::dodge::clear(app_handle);
+    if let Err(e) = app_handle.state::<WindowMotion>().move_to(&main_window, target, motion) {
+        println!("⚠️ Failed to move main window: {}", e);
+    }
+}
+
+/// The placement for the next rotation step, or `None` when no grid slot is
+/// available.
+fn rotation_request(app_handle: &tauri::AppHandle, settings: &Settings) -> Option<PlacementRequest> {
+    if settings.random_position {
+        return Some(PlacementRequest::Random {
+            monitor: settings.preferred_monitor.clone(),
+        });
+    }
+
+    let main_window = app_handle.get_window("main")?;
+    let area = crate::target_monitor(&main_window, &settings.preferred_monitor)
+        .map(|monitor| monitor.logical_area())
+        .unwrap_or(layout::Rect::new(0.0, 0.0, 1920.0, 1080.0));
+    let grid = layout::grid_dimensions(area.size(), settings.auto_detect_grid, settings.manual_grid_cols, settings.manual_grid_rows);
+
+    let rotation = app_handle.state::<PositionRotation>();
+    let slot = rotation.advance_slot(settings, grid.slot_count() as i32, crate::clock_seed())?;
+    Some(PlacementRequest::Grid {
+        slot,
+        auto_detect_grid: settings.auto_detect_grid,
+        cols: settings.manual_grid_cols,
+        rows: settings.manual_grid_rows,
+        monitor: settings.preferred_monitor.clone(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn never_stays_on_the_current_slot() {
+        for seed in 0..20 {
+            assert_ne!(next_slot(12, 4, &[], &[], seed), Some(4));
+        }
+    }
+
+    #[test]
+    fn skips_excluded_and_recent_slots() {
+        // Only slot 3 is neither current, excluded nor recent
+        for seed in 0..10 {
+            assert_eq!(next_slot(6, 0, &[1, 2], &[4, 5], seed), Some(3));
+        }
+    }
+
+    #[test]
+    fn reuses_recent_slots_when_nothing_else_is_left() {
+        assert_eq!(next_slot(3, 0, &[1], &[2], 7), Some(1));
+    }
+
+    #[test]
+    fn gives_up_when_every_other_slot_is_excluded() {
+        assert_eq!(next_slot(4, 0, &[], &[1, 2, 3], 0), None);
+        assert_eq!(next_slot(1, 0, &[], &[], 0), None);
+    }
+
+    #[test]
+    fn history_avoids_the_last_slots() {
+        let rotation = PositionRotation::default();
+        let settings = Settings {
+            position_grid: 0,
+            rotation_avoid_recent: 2,
+            ..Settings::default()
+        };
+        let mut visited = vec![0];
+        for seed in 0..12 {
+            let slot = rotation.advance_slot(&settings, 4, seed).unwrap();
+            // The previous two positions are never revisited
+            assert!(!visited.iter().rev().take(2).any(|previous| *previous == slot));
+            visited.push(slot);
+        }
+    }
+}
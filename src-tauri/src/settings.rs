@@ -0,0 +1,1051 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use tauri::Manager;
+
+use crate::layout::{Anchor, OffsetUnit};
+use crate::monitor_layout::MonitorDescriptor;
+use crate::shortcuts::{self, Keymap};
+use crate::window_motion::{self, Easing};
+
+mod migrations;
+mod profiles;
+
+pub use migrations::{MigrationContext, CURRENT_VERSION};
+pub use profiles::{Profiles, ProfilesInfo, DEFAULT_PROFILE};
+
+const SETTINGS_FILE: &str = "settings.json";
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum FontWeight {
+    Normal,
+    Bold,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum CardSize {
+    Small,
+    Large,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum GridInputType {
+    Grid,
+    Screen,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum CardInfoAnimation {
+    Flip,
+    Slide,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum CardInfoDirection {
+    Left,
+    Right,
+    Top,
+    Bottom,
+}
+
+/// What counts towards the next position rotation.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum RotationTrigger {
+    Cards,
+    Minutes,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum CardInfoCloseBehavior {
+    Manual,
+    MouseLeave,
+    Both,
+}
+
+/// Which monitor the flashcard should be placed on.
+///
+/// Monitors are identified by name and geometry (`{ "name": "DP-1", ... }`)
+/// so the choice survives displays being plugged in. Bare indices from older
+/// settings files are turned into identities when the file is migrated, but
+/// are still read (as numbers or strings) from patches and hand edits.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub enum PreferredMonitor {
+    #[default]
+    Auto,
+    Primary,
+    Current,
+    Index(usize),
+    Monitor(MonitorDescriptor),
+}
+
+impl Serialize for PreferredMonitor {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            PreferredMonitor::Auto => serializer.serialize_str("auto"),
+            PreferredMonitor::Primary => serializer.serialize_str("primary"),
+            PreferredMonitor::Current => serializer.serialize_str("current"),
+            PreferredMonitor::Index(index) => serializer.serialize_u64(*index as u64),
+            PreferredMonitor::Monitor(monitor) => monitor.serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for PreferredMonitor {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+
+        match serde_json::Value::deserialize(deserializer)? {
+            serde_json::Value::Number(n) => n
+                .as_u64()
+                .map(|index| PreferredMonitor::Index(index as usize))
+                .ok_or_else(|| D::Error::custom(format!("invalid monitor index: {}", n))),
+            serde_json::Value::String(s) => match s.as_str() {
+                "auto" => Ok(PreferredMonitor::Auto),
+                "primary" => Ok(PreferredMonitor::Primary),
+                "current" => Ok(PreferredMonitor::Current),
+                other => other
+                    .parse::<usize>()
+                    .map(PreferredMonitor::Index)
+                    .map_err(|_| D::Error::custom(format!("unknown monitor preference: {}", other))),
+            },
+            serde_json::Value::Object(monitor) => serde_json::from_value(serde_json::Value::Object(monitor))
+                .map(PreferredMonitor::Monitor)
+                .map_err(|e| D::Error::custom(format!("invalid monitor: {}", e))),
+            other => Err(D::Error::custom(format!("invalid monitor preference: {}", other))),
+        }
+    }
+}
+
+impl std::fmt::Display for PreferredMonitor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PreferredMonitor::Auto => write!(f, "auto"),
+            PreferredMonitor::Primary => write!(f, "primary"),
+            PreferredMonitor::Current => write!(f, "current"),
+            PreferredMonitor::Index(index) => write!(f, "{}", index),
+            PreferredMonitor::Monitor(monitor) => write!(f, "{}", monitor),
+        }
+    }
+}
+
+/// Everything the frontend used to keep in localStorage as `AppearanceSettings`.
+/// Field names are camelCase on the wire so the TypeScript interface is unchanged.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Settings {
+    // Font settings
+    pub font_size: f64,
+    pub text_color: String,
+    pub font_family: String,
+    pub font_weight: FontWeight,
+    pub text_shadow: bool,
+
+    // Card settings
+    pub card_transparent: bool,
+    pub card_color: String,
+    pub card_opacity: f64,
+    pub card_size: CardSize,
+    pub auto_card_size: bool,
+
+    pub color_rotation: bool,
+    pub dark_mode: bool,
+    pub sync_symbol_color: bool,
+
+    // Multi-space settings (macOS)
+    pub show_on_all_spaces: bool,
+
+    // Whether the card ignores mouse events
+    pub click_through: bool,
+
+    // Position settings
+    pub position_grid: i32,
+    pub random_position: bool,
+    pub manual_position: bool,
+    pub manual_x: f64,
+    pub manual_y: f64,
+
+    // Anchor placement: pinned to a corner/edge, offset inwards
+    pub anchor_position: bool,
+    pub anchor: Anchor,
+    pub anchor_offset_x: f64,
+    pub anchor_offset_y: f64,
+    pub anchor_offset_unit: OffsetUnit,
+
+    // Position rotation: move the card every N cards or minutes
+    pub position_rotation: bool,
+    pub rotation_trigger: RotationTrigger,
+    pub rotation_interval: u32,
+    pub rotation_avoid_recent: u32,
+    pub rotation_excluded_slots: Vec<i32>,
+    pub rotation_animate: bool,
+
+    // How the card moves when it is repositioned
+    pub smooth_movement: bool,
+    pub movement_duration_ms: u32,
+    pub movement_easing: Easing,
+
+    // Snapping when the card is dropped after a drag
+    pub snap_on_drop: bool,
+    pub snap_threshold: f64,
+    pub snap_to_windows: bool,
+
+    // Dodge mode (X11): move away from the pointer and the focused window
+    pub dodge_mode: bool,
+    pub dodge_margin: f64,
+
+    // Global shortcut per action
+    pub shortcuts: Keymap,
+
+    // Grid override settings
+    pub auto_detect_grid: bool,
+    pub manual_grid_input_type: GridInputType,
+    pub manual_grid_cols: i32,
+    pub manual_grid_rows: i32,
+    pub manual_screen_width: f64,
+    pub manual_screen_height: f64,
+
+    // Monitor settings
+    pub preferred_monitor: PreferredMonitor,
+    pub allow_cross_monitor_positioning: bool,
+
+    // Topic settings
+    pub selected_topic: String,
+    pub selected_topic_code: String,
+    pub selected_topic_icon: String,
+    pub favorite_topics: Vec<String>,
+
+    // Content type settings
+    pub selected_content_type: String,
+    pub selected_content_type_code: String,
+    pub selected_content_type_icon: String,
+    pub favorite_content_types: Vec<String>,
+
+    // Card info settings
+    pub card_info_animation: CardInfoAnimation,
+    pub card_info_direction: CardInfoDirection,
+    pub card_info_show_translation: bool,
+    pub card_info_show_definition: bool,
+    pub card_info_show_part_of_speech: bool,
+    pub card_info_show_examples: bool,
+    pub card_info_show_pronunciation: bool,
+    pub card_info_max_examples: u32,
+    pub card_info_close_behavior: CardInfoCloseBehavior,
+
+    // AI provider settings, checked by check_ollama_connection; "ollama" is
+    // the only provider so far and ai_model must be installed there
+    pub ai_provider: String,
+    pub ai_endpoint: String,
+    pub ai_model: String,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            font_size: 40.0,
+            text_color: "#FFD700".to_string(),
+            font_family: "system-ui".to_string(),
+            font_weight: FontWeight::Bold,
+            text_shadow: true,
+            card_transparent: true,
+            card_color: "rgba(255, 255, 255, 0.1)".to_string(),
+            card_opacity: 0.1,
+            card_size: CardSize::Small,
+            auto_card_size: true,
+            color_rotation: true,
+            dark_mode: false,
+            sync_symbol_color: false,
+            show_on_all_spaces: true,
+            click_through: false,
+            position_grid: 4, // center-left position
+            random_position: false,
+            manual_position: false,
+            manual_x: 100.0,
+            manual_y: 100.0,
+            anchor_position: false,
+            anchor: Anchor::BottomRight,
+            anchor_offset_x: 24.0,
+            anchor_offset_y: 24.0,
+            anchor_offset_unit: OffsetUnit::Px,
+            position_rotation: false,
+            rotation_trigger: RotationTrigger::Minutes,
+            rotation_interval: 15,
+            rotation_avoid_recent: 3,
+            rotation_excluded_slots: Vec::new(),
+            rotation_animate: true,
+            smooth_movement: true,
+            movement_duration_ms: 350,
+            movement_easing: Easing::EaseInOut,
+            snap_on_drop: true,
+            snap_threshold: 24.0,
+            snap_to_windows: false,
+            dodge_mode: false,
+            dodge_margin: 16.0,
+            shortcuts: shortcuts::default_keymap(),
+            auto_detect_grid: true,
+            manual_grid_input_type: GridInputType::Grid,
+            manual_grid_cols: 4,
+            manual_grid_rows: 3,
+            manual_screen_width: 1920.0,
+            manual_screen_height: 1080.0,
+            preferred_monitor: PreferredMonitor::Auto,
+            allow_cross_monitor_positioning: true,
+            selected_topic: "French".to_string(),
+            selected_topic_code: "FR".to_string(),
+            selected_topic_icon: "🇫🇷".to_string(),
+            favorite_topics: vec!["EN".into(), "FR".into(), "ES".into(), "DE".into()],
+            selected_content_type: "Words".to_string(),
+            selected_content_type_code: "WORDS".to_string(),
+            selected_content_type_icon: "🔤".to_string(),
+            favorite_content_types: vec![
+                "WORDS".into(),
+                "SENTENCES".into(),
+                "EXPRESSIONS".into(),
+                "PHRASAL_VERBS".into(),
+            ],
+            card_info_animation: CardInfoAnimation::Flip,
+            card_info_direction: CardInfoDirection::Right,
+            card_info_show_translation: true,
+            card_info_show_definition: true,
+            card_info_show_part_of_speech: true,
+            card_info_show_examples: true,
+            card_info_show_pronunciation: false,
+            card_info_max_examples: 2,
+            card_info_close_behavior: CardInfoCloseBehavior::Both,
+            ai_provider: "ollama".to_string(),
+            ai_endpoint: "http://localhost:11434".to_string(),
+            ai_model: "llama3".to_string(),
+        }
+    }
+}
+
+impl Settings {
+    /// Applies a partial update (a subset of the camelCase fields) on top of
+    /// these settings. Unknown keys and values of the wrong type are rejected
+    /// so a bad patch can never leave a half-applied state behind; numbers
+    /// outside their range are clamped.
+    pub fn merged_with(&self, patch: &serde_json::Value) -> Result<Settings, String> {
+        let patch = patch
+            .as_object()
+            .ok_or_else(|| "Settings update must be an object".to_string())?;
+
+        let mut merged = serde_json::to_value(self).map_err(|e| e.to_string())?;
+        let fields = merged
+            .as_object_mut()
+            .ok_or_else(|| "Settings did not serialize to an object".to_string())?;
+
+        for (key, value) in patch {
+            if !fields.contains_key(key) {
+                return Err(format!("Unknown setting: {}", key));
+            }
+            fields.insert(key.clone(), value.clone());
+        }
+
+        serde_json::from_value::<Settings>(merged)
+            .map(Settings::validated)
+            .map_err(|e| format!("Invalid settings update: {}", e))
+    }
+
+    /// Builds settings from a stored object one field at a time, so a single
+    /// bad value falls back to its default instead of resetting everything.
+    /// Returns a warning for every field that was dropped.
+    pub fn from_stored(stored: &serde_json::Value) -> (Settings, Vec<String>) {
+        let mut settings = Settings::default();
+        let mut warnings = Vec::new();
+
+        let Some(fields) = stored.as_object() else {
+            warnings.push("Stored settings are not an object".to_string());
+            return (settings, warnings);
+        };
+
+        for (key, value) in fields {
+            let patch = serde_json::json!({ key.clone(): value.clone() });
+            match settings.merged_with(&patch) {
+                Ok(merged) => settings = merged,
+                Err(e) => warnings.push(format!("Ignoring {}: {}", key, e)),
+            }
+        }
+
+        (settings, warnings)
+    }
+
+    /// The part of a stored object that can be applied on top of these
+    /// settings: every field that passes validation, as validated, and
+    /// nothing else, so fields the object leaves out keep their value here.
+    /// Returns a warning for every field that was dropped.
+    pub fn patch_from_stored(&self, stored: &serde_json::Value) -> (serde_json::Value, Vec<String>) {
+        let mut patch = serde_json::Map::new();
+        let mut warnings = Vec::new();
+
+        let Some(fields) = stored.as_object() else {
+            warnings.push("Stored settings are not an object".to_string());
+            return (serde_json::Value::Object(patch), warnings);
+        };
+
+        for (key, value) in fields {
+            let merged = self
+                .merged_with(&serde_json::json!({ key.clone(): value.clone() }))
+                .and_then(|merged| serde_json::to_value(merged).map_err(|e| e.to_string()));
+            match merged {
+                Ok(mut merged) => {
+                    patch.insert(key.clone(), merged[key.as_str()].take());
+                }
+                Err(e) => warnings.push(format!("Ignoring {}: {}", key, e)),
+            }
+        }
+
+        (serde_json::Value::Object(patch), warnings)
+    }
+
+    /// Clamps numeric fields into the ranges the UI and placement code expect.
+    pub fn validated(mut self) -> Settings {
+        self.font_size = clamp_or(self.font_size, 8.0, 200.0, 40.0);
+        self.card_opacity = clamp_or(self.card_opacity, 0.0, 1.0, 0.1);
+        self.position_grid = self.position_grid.max(0);
+        self.manual_x = finite_or(self.manual_x, 100.0);
+        self.manual_y = finite_or(self.manual_y, 100.0);
+        let max_offset = match self.anchor_offset_unit {
+            OffsetUnit::Px => 16384.0,
+            OffsetUnit::Percent => 100.0,
+        };
+        self.anchor_offset_x = clamp_or(self.anchor_offset_x, -max_offset, max_offset, 0.0);
+        self.anchor_offset_y = clamp_or(self.anchor_offset_y, -max_offset, max_offset, 0.0);
+        self.rotation_interval = self.rotation_interval.clamp(1, 1440);
+        self.rotation_avoid_recent = self.rotation_avoid_recent.min(16);
+        self.rotation_excluded_slots.retain(|slot| (0..144).contains(slot));
+        self.rotation_excluded_slots.sort_unstable();
+        self.rotation_excluded_slots.dedup();
+        self.movement_duration_ms = self.movement_duration_ms.min(window_motion::MAX_DURATION_MS);
+        self.snap_threshold = clamp_or(self.snap_threshold, 0.0, 200.0, 24.0);
+        self.dodge_margin = clamp_or(self.dodge_margin, 0.0, 200.0, 16.0);
+        self.manual_grid_cols = self.manual_grid_cols.clamp(1, 12);
+        self.manual_grid_rows = self.manual_grid_rows.clamp(1, 12);
+        self.manual_screen_width = clamp_or(self.manual_screen_width, 320.0, 16384.0, 1920.0);
+        self.manual_screen_height = clamp_or(self.manual_screen_height, 240.0, 16384.0, 1080.0);
+        self.card_info_max_examples = self.card_info_max_examples.min(10);
+        self.shortcuts = shortcuts::completed(self.shortcuts);
+        self
+    }
+}
+
+fn finite_or(value: f64, fallback: f64) -> f64 {
+    if value.is_finite() { value } else { fallback }
+}
+
+fn clamp_or(value: f64, min: f64, max: f64, fallback: f64) -> f64 {
+    finite_or(value, fallback).clamp(min, max)
+}
+
+/// The result of reading a settings file from disk.
+#[derive(Debug)]
+pub struct LoadedSettings {
+    pub profiles: Profiles,
+    pub warnings: Vec<String>,
+    /// True when the file was written with an older schema and should be re-saved
+    pub migrated: bool,
+}
+
+/// Parses and migrates the contents of a settings file. Errors are reserved
+/// for documents that cannot be interpreted at all (bad JSON, newer schema);
+/// individual bad fields only produce warnings.
+pub fn parse_document(contents: &str, context: &MigrationContext) -> Result<LoadedSettings, String> {
+    let raw: serde_json::Value = serde_json::from_str(contents).map_err(|e| format!("Invalid JSON: {}", e))?;
+    let migrated = migrations::version_of(&raw) < CURRENT_VERSION;
+    let document = migrations::migrate(raw, context)?;
+
+    let stored_profiles = document["profiles"]
+        .as_object()
+        .ok_or_else(|| "\"profiles\" must be an object".to_string())?;
+
+    let mut warnings = Vec::new();
+    let mut profiles = BTreeMap::new();
+    for (name, stored) in stored_profiles {
+        let (settings, profile_warnings) = Settings::from_stored(stored);
+        warnings.extend(profile_warnings.into_iter().map(|w| format!("[{}] {}", name, w)));
+        profiles.insert(name.clone(), settings);
+    }
+
+    let mut layout_bindings = BTreeMap::new();
+    if let Some(bindings) = document["layoutBindings"].as_object() {
+        for (fingerprint, profile) in bindings {
+            match profile.as_str() {
+                Some(profile) if profiles.contains_key(profile) => {
+                    layout_bindings.insert(fingerprint.clone(), profile.to_string());
+                }
+                _ => warnings.push(format!("Ignoring layout binding to unknown profile {}", profile)),
+            }
+        }
+    }
+
+    let active = document["activeProfile"].as_str().unwrap_or(DEFAULT_PROFILE).to_string();
+    let profiles = Profiles::from_parts(active, profiles, layout_bindings);
+
+    Ok(LoadedSettings { profiles, warnings, migrated })
+}
+
+/// On-disk envelope around the profiles, carrying the schema version.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SettingsFile<'a> {
+    version: u32,
+    active_profile: &'a str,
+    profiles: &'a BTreeMap<String, Settings>,
+    layout_bindings: &'a BTreeMap<String, String>,
+}
+
+impl<'a> SettingsFile<'a> {
+    fn new(profiles: &'a Profiles) -> Self {
+        SettingsFile {
+            version: CURRENT_VERSION,
+            active_profile: profiles.active_name(),
+            profiles: profiles.all(),
+            layout_bindings: profiles.layout_bindings(),
+        }
+    }
+}
+
+/// What migrations need to know about this machine: the connected monitors,
+/// in the order old settings counted them.
+fn migration_context(app_handle: &tauri::AppHandle) -> MigrationContext {
+    let monitors = app_handle
+        .get_window("main")
+        .and_then(|window| window.available_monitors().ok())
+        .unwrap_or_default();
+    MigrationContext {
+        monitors: monitors.iter().map(MonitorDescriptor::from_monitor).collect(),
+    }
+}
+
+/// Backend-owned settings, persisted as JSON in the app config directory.
+/// This is the single source of truth; windows read the active profile with
+/// `get_settings` and receive every change through the `settings-updated`
+/// event, while profile list changes go out as `profiles-changed`.
+pub struct SettingsStore {
+    path: Option<PathBuf>,
+    current: Mutex<Profiles>,
+    /// Set while the file on disk holds an external edit that failed to
+    /// parse, so the next save backs it up instead of silently replacing it.
+    unparsed_edit: AtomicBool,
+}
+
+impl SettingsStore {
+    pub fn load(app_handle: &tauri::AppHandle) -> Self {
+        let path = app_handle
+            .path_resolver()
+            .app_config_dir()
+            .map(|dir| dir.join(SETTINGS_FILE));
+
+        let mut needs_save = false;
+        let current = match &path {
+            Some(path) if path.exists() => match fs::read_to_string(path).map_err(|e| e.to_string()).and_then(|c| parse_document(&c, &migration_context(app_handle))) {
+                Ok(loaded) => {
+                    for warning in &loaded.warnings {
+                        println!("⚠️ Settings: {}", warning);
+                    }
+                    if loaded.migrated {
+                        // Keep the pre-migration file around in case the upgrade loses something
+                        backup(path, "pre-migration");
+                        needs_save = true;
+                    }
+                    println!("⚙️ Loaded settings from {} (profile \"{}\")", path.display(), loaded.profiles.active_name());
+                    loaded.profiles
+                }
+                Err(e) => {
+                    // Preserve the unreadable file before anything can overwrite it
+                    println!("⚠️ Failed to load {}: {}, using defaults", path.display(), e);
+                    backup(path, "invalid");
+                    Profiles::default()
+                }
+            },
+            Some(path) => {
+                println!("⚙️ No settings file at {}, using defaults", path.display());
+                Profiles::default()
+            }
+            None => {
+                println!("⚠️ Could not resolve app config dir, settings will not be persisted");
+                Profiles::default()
+            }
+        };
+
+        let store = SettingsStore {
+            path,
+            current: Mutex::new(current),
+            unparsed_edit: AtomicBool::new(false),
+        };
+
+        if needs_save {
+            if let Err(e) = store.save(&crate::lock(&store.current)) {
+                println!("⚠️ Failed to save migrated settings: {}", e);
+            }
+        }
+
+        store
+    }
+
+    /// Settings of the active profile.
+    pub fn get(&self) -> Settings {
+        crate::lock(&self.current).active().clone()
+    }
+
+    pub fn profiles(&self) -> ProfilesInfo {
+        crate::lock(&self.current).info()
+    }
+
+    /// Runs `f` against the full profile set without copying it.
+    pub fn with_profiles<T>(&self, f: impl FnOnce(&Profiles) -> T) -> T {
+        f(&crate::lock(&self.current))
+    }
+
+    /// Validates and persists `patch` into the active profile, then
+    /// broadcasts the result to every window. The lock is held across save
+    /// and emit so concurrent updates reach the file and the windows in the
+    /// same order. Rotation, shortcuts and the tray are updated after the
+    /// lock is released, from the settings as they are by then, so an
+    /// update that finished first is never undone by a slower one.
+    pub fn update(&self, app_handle: &tauri::AppHandle, patch: &serde_json::Value) -> Result<Settings, String> {
+        let (updated, changed) = {
+            let mut current = crate::lock(&self.current);
+            let updated = current.active().merged_with(patch)?;
+            let changed = updated != *current.active();
+            if changed {
+                let mut next = current.clone();
+                next.set_active_settings(updated.clone());
+                self.save(&next)?;
+                *current = next;
+            }
+            // Emitting only queues the event for the webviews, so it cannot block here
+            let _ = app_handle.emit_all("settings-updated", current.active());
+            (updated, changed)
+        };
+
+        // Shortcut registration waits on the main thread, so the lock is released first
+        if changed {
+            let latest = self.get();
+            crate::position_rotation::schedule(app_handle, &latest);
+            crate::dodge::schedule(app_handle, &latest);
+            shortcuts::apply(app_handle, &latest.shortcuts);
+            crate::tray::refresh(app_handle);
+        }
+
+        Ok(updated)
+    }
+
+    /// Applies a change to the profile set (create, clone, delete, switch),
+    /// persists it and notifies windows and the tray. When the active profile
+    /// changes, its window-level effects are applied too.
+    pub fn modify_profiles<F, T>(&self, app_handle: &tauri::AppHandle, change: F) -> Result<T, String>
+    where
+        F: FnOnce(&mut Profiles) -> Result<T, String>,
+    {
+        let (previous, next, result) = {
+            let mut current = crate::lock(&self.current);
+            let mut next = current.clone();
+            let result = change(&mut next)?;
+            self.save(&next)?;
+            let previous = std::mem::replace(&mut *current, next.clone());
+            (previous, next, result)
+        };
+
+        self.broadcast(app_handle, &previous, &next);
+        Ok(result)
+    }
+
+    /// Re-reads the settings file after it was changed outside the app.
+    /// The file itself is never rewritten here, so a half-typed edit or an
+    /// invalid value is reported and left for the user to fix; should the app
+    /// save before it is fixed, the edit is backed up first.
+    fn reload(&self, app_handle: &tauri::AppHandle) {
+        let Some(path) = self.path.as_ref() else {
+            return;
+        };
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) => {
+                // Editors that save by delete + rename briefly leave no file behind
+                println!("⚠️ Failed to read {}: {}", path.display(), e);
+                return;
+            }
+        };
+
+        let loaded = match parse_document(&contents, &migration_context(app_handle)) {
+            Ok(loaded) => loaded,
+            Err(e) => {
+                println!("⚠️ Ignoring external settings edit: {}", e);
+                self.unparsed_edit.store(true, Ordering::Relaxed);
+                report_errors(app_handle, path, vec![e]);
+                return;
+            }
+        };
+        self.unparsed_edit.store(false, Ordering::Relaxed);
+
+        if !loaded.warnings.is_empty() {
+            for warning in &loaded.warnings {
+                println!("⚠️ Settings: {}", warning);
+            }
+            report_errors(app_handle, path, loaded.warnings);
+        }
+
+        let previous = {
+            let mut current = crate::lock(&self.current);
+            if loaded.profiles == *current {
+                // Our own save, or an edit that only touched formatting
+                return;
+            }
+            std::mem::replace(&mut *current, loaded.profiles.clone())
+        };
+
+        println!("🔄 Reloaded settings from {}", path.display());
+        self.broadcast(app_handle, &previous, &loaded.profiles);
+    }
+
+    /// Tells windows and the tray about a change that did not originate from
+    /// a window, and re-applies window-level effects of the active profile.
+    fn broadcast(&self, app_handle: &tauri::AppHandle, previous: &Profiles, next: &Profiles) {
+        if previous.info() != next.info() {
+            let _ = app_handle.emit_all("profiles-changed", next.info());
+            crate::tray::refresh(app_handle);
+        }
+
+        if previous.layout_bindings() != next.layout_bindings() {
+            let _ = app_handle.emit_all("layout-bindings-changed", next.layout_bindings());
+        }
+
+        if previous.active() != next.active() {
+            let _ = app_handle.emit_all("settings-updated", next.active());
+            crate::apply_window_settings(app_handle, previous.active(), next.active());
+            crate::position_rotation::schedule(app_handle, next.active());
+            crate::dodge::schedule(app_handle, next.active());
+            shortcuts::apply(app_handle, &next.active().shortcuts);
+        }
+    }
+
+    fn save(&self, profiles: &Profiles) -> Result<(), String> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        }
+
+        if self.unparsed_edit.swap(false, Ordering::Relaxed) {
+            println!("⚠️ Saving over an external settings edit that could not be read");
+            backup(path, "unparsed-edit");
+        }
+
+        // Write to a sibling file and rename so a crash never leaves a truncated config
+        let contents = serde_json::to_string_pretty(&SettingsFile::new(profiles)).map_err(|e| e.to_string())?;
+        let tmp_path = path.with_extension("json.tmp");
+        fs::write(&tmp_path, contents).map_err(|e| format!("Failed to write {}: {}", tmp_path.display(), e))?;
+        fs::rename(&tmp_path, path).map_err(|e| format!("Failed to replace {}: {}", path.display(), e))?;
+
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Clone)]
+struct SettingsErrorPayload {
+    path: String,
+    errors: Vec<String>,
+}
+
+fn report_errors(app_handle: &tauri::AppHandle, path: &Path, errors: Vec<String>) {
+    let payload = SettingsErrorPayload {
+        path: path.display().to_string(),
+        errors,
+    };
+    let _ = app_handle.emit_all("settings-error", payload);
+}
+
+/// Watches the settings file for edits made outside the app (dotfile
+/// managers, text editors) and hot-reloads them, re-applying window-level
+/// effects such as position, spaces and click-through.
+pub fn watch(app_handle: tauri::AppHandle) {
+    use notify::{RecursiveMode, Watcher};
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    let store = app_handle.state::<SettingsStore>();
+    let Some(path) = store.path.clone() else {
+        return;
+    };
+    let Some(dir) = path.parent().map(Path::to_path_buf) else {
+        return;
+    };
+
+    if let Err(e) = fs::create_dir_all(&dir) {
+        println!("⚠️ Not watching settings, failed to create {}: {}", dir.display(), e);
+        return;
+    }
+
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = match notify::recommended_watcher(tx) {
+        Ok(watcher) => watcher,
+        Err(e) => {
+            println!("⚠️ Failed to create settings watcher: {}", e);
+            return;
+        }
+    };
+
+    // Watch the directory rather than the file: editors commonly save by
+    // writing a temp file and renaming it over the original
+    if let Err(e) = watcher.watch(&dir, RecursiveMode::NonRecursive) {
+        println!("⚠️ Failed to watch {}: {}", dir.display(), e);
+        return;
+    }
+
+    println!("👀 Watching {} for external edits", path.display());
+
+    std::thread::spawn(move || {
+        // Keep the watcher alive for as long as the thread runs
+        let _watcher = watcher;
+        let touches_settings = |event: &notify::Result<notify::Event>| match event {
+            Ok(event) => event.paths.iter().any(|p| p.file_name() == path.file_name()),
+            Err(_) => false,
+        };
+
+        while let Ok(event) = rx.recv() {
+            if !touches_settings(&event) {
+                continue;
+            }
+
+            // Editors emit several events per save; wait for them to settle
+            while rx.recv_timeout(Duration::from_millis(200)).is_ok() {}
+
+            app_handle.state::<SettingsStore>().reload(&app_handle);
+        }
+    });
+}
+
+fn backup(path: &Path, reason: &str) {
+    let backup_path = path.with_extension(format!("{}.json.bak", reason));
+    match fs::copy(path, &backup_path) {
+        Ok(_) => println!("💾 Backed up settings to {}", backup_path.display()),
+        Err(e) => println!("⚠️ Failed to back up settings to {}: {}", backup_path.display(), e),
+    }
+}
+
+#[tauri::command]
+pub fn get_settings(store: tauri::State<SettingsStore>) -> Settings {
+    store.get()
+}
+
+#[tauri::command]
+pub fn update_settings(app_handle: tauri::AppHandle, store: tauri::State<SettingsStore>, patch: serde_json::Value) -> Result<Settings, String> {
+    println!("⚙️ update_settings called with: {}", patch);
+    store.update(&app_handle, &patch)
+}
+
+/// Imports settings the frontend kept in localStorage before they moved to
+/// the backend. Fields that no longer exist or fail validation are skipped
+/// with a warning instead of rejecting the whole import, and fields the old
+/// data does not mention keep their value in the active profile.
+#[tauri::command]
+pub fn import_legacy_settings(app_handle: tauri::AppHandle, store: tauri::State<SettingsStore>, mut stored: serde_json::Value) -> Result<Settings, String> {
+    // Old data counted monitors by index; store the monitor it meant instead
+    if let Some(preference) = stored.get_mut("preferredMonitor") {
+        migrations::monitor_index_to_identity(preference, &migration_context(&app_handle))?;
+    }
+    let (patch, warnings) = store.get().patch_from_stored(&stored);
+    for warning in &warnings {
+        println!("⚠️ Legacy settings: {}", warning);
+    }
+    store.update(&app_handle, &patch)
+}
+
+#[tauri::command]
+pub fn list_profiles(store: tauri::State<SettingsStore>) -> ProfilesInfo {
+    store.profiles()
+}
+
+#[tauri::command]
+pub fn create_profile(app_handle: tauri::AppHandle, store: tauri::State<SettingsStore>, name: String) -> Result<ProfilesInfo, String> {
+    println!("👤 Creating profile \"{}\"", name);
+    store.modify_profiles(&app_handle, |profiles| {
+        profiles.create(&name)?;
+        Ok(profiles.info())
+    })
+}
+
+#[tauri::command]
+pub fn clone_profile(app_handle: tauri::AppHandle, store: tauri::State<SettingsStore>, source: String, name: String) -> Result<ProfilesInfo, String> {
+    println!("👤 Cloning profile \"{}\" as \"{}\"", source, name);
+    store.modify_profiles(&app_handle, |profiles| {
+        profiles.clone_profile(&source, &name)?;
+        Ok(profiles.info())
+    })
+}
+
+#[tauri::command]
+pub fn delete_profile(app_handle: tauri::AppHandle, store: tauri::State<SettingsStore>, name: String) -> Result<ProfilesInfo, String> {
+    println!("👤 Deleting profile \"{}\"", name);
+    store.modify_profiles(&app_handle, |profiles| {
+        profiles.delete(&name)?;
+        Ok(profiles.info())
+    })
+}
+
+#[tauri::command]
+pub fn switch_profile(app_handle: tauri::AppHandle, store: tauri::State<SettingsStore>, name: String) -> Result<Settings, String> {
+    println!("👤 Switching to profile \"{}\"", name);
+    store.modify_profiles(&app_handle, |profiles| {
+        profiles.switch(&name)?;
+        Ok(profiles.active().clone())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn merged_with_rejects_unknown_keys() {
+        let err = Settings::default().merged_with(&json!({ "noSuchSetting": true })).unwrap_err();
+        assert!(err.contains("noSuchSetting"));
+    }
+
+    #[test]
+    fn merged_with_rejects_wrong_types() {
+        assert!(Settings::default().merged_with(&json!({ "cardSize": "huge" })).is_err());
+        assert!(Settings::default().merged_with(&json!({ "fontSize": "big" })).is_err());
+    }
+
+    #[test]
+    fn merged_with_clamps_out_of_range_numbers() {
+        let settings = Settings::default()
+            .merged_with(&json!({ "manualGridCols": 0, "cardOpacity": 3.5, "positionGrid": -2 }))
+            .unwrap();
+        assert_eq!(settings.manual_grid_cols, 1);
+        assert_eq!(settings.card_opacity, 1.0);
+        assert_eq!(settings.position_grid, 0);
+    }
+
+    #[test]
+    fn from_stored_keeps_valid_fields_next_to_invalid_ones() {
+        let (settings, warnings) = Settings::from_stored(&json!({
+            "positionGrid": 9,
+            "manualX": 1200,
+            "cardInfoCloseBehavior": "sometimes",
+        }));
+        assert_eq!(settings.position_grid, 9);
+        assert_eq!(settings.manual_x, 1200.0);
+        assert_eq!(settings.card_info_close_behavior, CardInfoCloseBehavior::Both);
+        assert_eq!(warnings.len(), 1);
+    }
+
+    #[test]
+    fn patch_from_stored_leaves_unmentioned_fields_alone() {
+        let current = Settings {
+            click_through: true,
+            ai_model: "mistral".to_string(),
+            ..Settings::default()
+        };
+        let (patch, warnings) = current.patch_from_stored(&json!({
+            "fontSize": 500,
+            "cardSize": "huge",
+        }));
+        assert_eq!(patch, json!({ "fontSize": 200.0 }));
+        assert_eq!(warnings.len(), 1);
+
+        let imported = current.merged_with(&patch).unwrap();
+        assert_eq!(imported.font_size, 200.0);
+        assert!(imported.click_through);
+        assert_eq!(imported.ai_model, "mistral");
+    }
+
+    #[test]
+    fn anchor_settings_are_read_and_clamped() {
+        let (settings, warnings) = Settings::from_stored(&json!({
+            "anchorPosition": true,
+            "anchor": "top-right",
+            "anchorOffsetX": 250,
+            "anchorOffsetUnit": "percent",
+        }));
+        assert!(warnings.is_empty());
+        assert!(settings.anchor_position);
+        assert_eq!(settings.anchor, Anchor::TopRight);
+        assert_eq!(settings.anchor_offset_unit, OffsetUnit::Percent);
+        assert_eq!(settings.anchor_offset_x, 100.0);
+    }
+
+    #[test]
+    fn rotation_settings_are_clamped() {
+        let settings = Settings::default()
+            .merged_with(&json!({
+                "positionRotation": true,
+                "rotationTrigger": "cards",
+                "rotationInterval": 0,
+                "rotationExcludedSlots": [7, -1, 2, 7, 500],
+            }))
+            .unwrap();
+        assert_eq!(settings.rotation_trigger, RotationTrigger::Cards);
+        assert_eq!(settings.rotation_interval, 1);
+        assert_eq!(settings.rotation_excluded_slots, vec![2, 7]);
+    }
+
+    #[test]
+    fn preferred_monitor_round_trips_by_identity() {
+        let by_name = json!({ "name": "DP-1", "x": 0, "y": 0, "width": 2560, "height": 1440 });
+        let preference: PreferredMonitor = serde_json::from_value(by_name.clone()).unwrap();
+        assert_eq!(preference, PreferredMonitor::Monitor(MonitorDescriptor::new("DP-1", 0, 0, 2560, 1440)));
+        assert_eq!(serde_json::to_value(&preference).unwrap(), by_name);
+        assert!(serde_json::from_value::<PreferredMonitor>(json!({ "name": "DP-1" })).is_err());
+    }
+
+    #[test]
+    fn parse_document_migrates_legacy_blob_without_resetting_layout() {
+        let loaded = parse_document(
+            r#"{ "positionGrid": 11, "manualGridCols": 6, "preferredMonitor": "1" }"#,
+            &MigrationContext {
+                monitors: vec![MonitorDescriptor::new("eDP-1", 0, 0, 1920, 1080), MonitorDescriptor::new("DP-1", 1920, 0, 2560, 1440)],
+            },
+        )
+        .unwrap();
+        let settings = loaded.profiles.active();
+        assert!(loaded.migrated);
+        assert_eq!(loaded.profiles.active_name(), DEFAULT_PROFILE);
+        assert_eq!(settings.position_grid, 11);
+        assert_eq!(settings.manual_grid_cols, 6);
+        assert_eq!(settings.preferred_monitor, PreferredMonitor::Monitor(MonitorDescriptor::new("DP-1", 1920, 0, 2560, 1440)));
+        // Fields added after the blob was written fall back to defaults
+        assert_eq!(settings.card_info_close_behavior, CardInfoCloseBehavior::Both);
+    }
+
+    #[test]
+    fn parse_document_round_trips_current_schema() {
+        let mut profiles = Profiles::single("Work", Settings { font_size: 28.0, ..Settings::default() });
+        profiles.create("Home").unwrap();
+        profiles.bind_layout("DP-1@0,0:2560x1440", "Work").unwrap();
+        let contents = serde_json::to_string(&SettingsFile::new(&profiles)).unwrap();
+        let loaded = parse_document(&contents, &MigrationContext::default()).unwrap();
+        assert!(!loaded.migrated);
+        assert!(loaded.warnings.is_empty());
+        assert_eq!(loaded.profiles, profiles);
+    }
+
+    #[test]
+    fn parse_document_prefixes_warnings_with_profile_name() {
+        let loaded = parse_document(
+            r#"{ "version": 3, "activeProfile": "Home", "profiles": { "Home": { "cardSize": "huge" } } }"#,
+            &MigrationContext::default(),
+        )
+        .unwrap();
+        assert_eq!(loaded.warnings.len(), 1);
+        assert!(loaded.warnings[0].starts_with("[Home]"));
+    }
+
+    #[test]
+    fn parse_document_rejects_garbage() {
+        assert!(parse_document("not json", &MigrationContext::default()).is_err());
+    }
+}
@@ -0,0 +1,286 @@
+use serde_json::{Map, Value};
+
+use crate::monitor_layout::MonitorDescriptor;
+
+/// Schema version written by this build. Bump it together with a new entry
+/// in `MIGRATIONS` whenever the on-disk layout or a field's meaning changes.
+pub const CURRENT_VERSION: u32 = 3;
+
+/// What migrations may need to know about the machine they run on.
+#[derive(Default)]
+pub struct MigrationContext {
+    /// Connected monitors in the order the OS enumerates them, which is the
+    /// order old settings counted monitor indices in
+    pub monitors: Vec<MonitorDescriptor>,
+}
+
+type Migration = fn(Value, &MigrationContext) -> Result<Value, String>;
+
+/// Ordered migration steps, indexed by the version they upgrade *from*.
+const MIGRATIONS: &[Migration] = &[v0_to_v1, v1_to_v2, v2_to_v3];
+
+/// Reads the schema version of a stored settings document. Files written
+/// before versioning existed are a bare settings object and count as v0.
+pub fn version_of(raw: &Value) -> u32 {
+    raw.get("version")
+        .and_then(Value::as_u64)
+        .map(|v| v as u32)
+        .unwrap_or(0)
+}
+
+/// Runs every migration needed to bring `raw` up to `CURRENT_VERSION`.
+/// Documents from a newer build are refused rather than guessed at.
+pub fn migrate(mut raw: Value, context: &MigrationContext) -> Result<Value, String> {
+    let mut version = version_of(&raw);
+
+    if version > CURRENT_VERSION {
+        return Err(format!(
+            "Settings were written by a newer version of Floatlearn (schema v{}, this build understands v{})",
+            version, CURRENT_VERSION
+        ));
+    }
+
+    while version < CURRENT_VERSION {
+        let step = MIGRATIONS[version as usize];
+        raw = step(raw, context).map_err(|e| format!("Migration v{} -> v{} failed: {}", version, version + 1, e))?;
+        version += 1;
+        println!("⚙️ Migrated settings to schema v{}", version);
+    }
+
+    Ok(raw)
+}
+
+/// v0 -> v1: wrap the bare object in a `{ version, settings }` envelope and
+/// normalize values the old localStorage store let through as strings.
+fn v0_to_v1(raw: Value, _: &MigrationContext) -> Result<Value, String> {
+    let Value::Object(mut settings) = raw else {
+        return Err("expected a settings object".to_string());
+    };
+
+    // The monitor <select> stored indices as strings ("1") next to keywords
+    if let Some(Value::String(monitor)) = settings.get("preferredMonitor") {
+        if let Ok(index) = monitor.parse::<u64>() {
+            settings.insert("preferredMonitor".to_string(), Value::from(index));
+        }
+    }
+
+    // Numeric inputs occasionally round-tripped as strings
+    for key in [
+        "fontSize",
+        "cardOpacity",
+        "positionGrid",
+        "manualX",
+        "manualY",
+        "manualGridCols",
+        "manualGridRows",
+        "manualScreenWidth",
+        "manualScreenHeight",
+        "cardInfoMaxExamples",
+    ] {
+        if let Some(Value::String(number)) = settings.get(key) {
+            if let Ok(parsed) = number.trim().parse::<f64>() {
+                settings.insert(key.to_string(), number_value(parsed));
+            }
+        }
+    }
+
+    let mut envelope = Map::new();
+    envelope.insert("version".to_string(), Value::from(1));
+    envelope.insert("settings".to_string(), Value::Object(settings));
+    Ok(Value::Object(envelope))
+}
+
+/// v1 -> v2: settings became named profiles; the existing settings turn
+/// into the active "Default" profile.
+fn v1_to_v2(raw: Value, _: &MigrationContext) -> Result<Value, String> {
+    let Value::Object(mut document) = raw else {
+        return Err("expected a settings document".to_string());
+    };
+    let settings = document
+        .remove("settings")
+        .ok_or_else(|| "missing \"settings\"".to_string())?;
+
+    let mut profiles = Map::new();
+    profiles.insert(super::profiles::DEFAULT_PROFILE.to_string(), settings);
+
+    let mut envelope = Map::new();
+    envelope.insert("version".to_string(), Value::from(2));
+    envelope.insert("activeProfile".to_string(), Value::from(super::profiles::DEFAULT_PROFILE));
+    envelope.insert("profiles".to_string(), Value::Object(profiles));
+    Ok(Value::Object(envelope))
+}
+
+/// v2 -> v3: monitor preferences stored as an index become the identity of
+/// the monitor at that index, so they survive monitors being re-enumerated.
+/// An index with no monitor behind it falls back to "auto".
+fn v2_to_v3(raw: Value, context: &MigrationContext) -> Result<Value, String> {
+    let Value::Object(mut document) = raw else {
+        return Err("expected a settings document".to_string());
+    };
+    let Some(Value::Object(profiles)) = document.get_mut("profiles") else {
+        return Err("missing \"profiles\"".to_string());
+    };
+
+    for settings in profiles.values_mut() {
+        if let Some(preference) = settings.get_mut("preferredMonitor") {
+            monitor_index_to_identity(preference, context)?;
+        }
+    }
+
+    document.insert("version".to_string(), Value::from(3));
+    Ok(Value::Object(document))
+}
+
+/// Replaces a monitor preference stored as an index (a number or a numeric
+/// string) with the identity of the monitor at that index, or "auto" when
+/// there is none. Keywords and identities are left as they are. Also used
+/// for settings imported from outside the settings file.
+pub fn monitor_index_to_identity(preference: &mut Value, context: &MigrationContext) -> Result<(), String> {
+    let index = match preference {
+        Value::Number(n) => n.as_u64(),
+        Value::String(s) => s.parse::<u64>().ok(),
+        _ => None,
+    };
+    if let Some(index) = index {
+        *preference = match context.monitors.get(index as usize) {
+            Some(monitor) => serde_json::to_value(monitor).map_err(|e| e.to_string())?,
+            None => Value::from("auto"),
+        };
+    }
+    Ok(())
+}
+
+fn number_value(n: f64) -> Value {
+    if n.fract() == 0.0 && n.abs() < i64::MAX as f64 {
+        Value::from(n as i64)
+    } else {
+        Value::from(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn none() -> MigrationContext {
+        MigrationContext::default()
+    }
+
+    #[test]
+    fn unversioned_documents_are_v0() {
+        assert_eq!(version_of(&json!({ "fontSize": 40 })), 0);
+        assert_eq!(version_of(&json!({ "version": 1, "settings": {} })), 1);
+    }
+
+    #[test]
+    fn v0_to_v1_wraps_settings_in_envelope() {
+        let migrated = v0_to_v1(json!({ "fontSize": 32, "positionGrid": 7 }), &none()).unwrap();
+        assert_eq!(
+            migrated,
+            json!({ "version": 1, "settings": { "fontSize": 32, "positionGrid": 7 } })
+        );
+    }
+
+    #[test]
+    fn v0_to_v1_normalizes_string_values() {
+        let migrated = v0_to_v1(
+            json!({
+                "preferredMonitor": "1",
+                "manualGridCols": "5",
+                "cardOpacity": "0.4",
+            }),
+            &none(),
+        )
+        .unwrap();
+        assert_eq!(migrated["settings"]["preferredMonitor"], json!(1));
+        assert_eq!(migrated["settings"]["manualGridCols"], json!(5));
+        assert_eq!(migrated["settings"]["cardOpacity"], json!(0.4));
+    }
+
+    #[test]
+    fn v0_to_v1_keeps_monitor_keywords() {
+        let migrated = v0_to_v1(json!({ "preferredMonitor": "current" }), &none()).unwrap();
+        assert_eq!(migrated["settings"]["preferredMonitor"], json!("current"));
+    }
+
+    #[test]
+    fn v0_to_v1_rejects_non_objects() {
+        assert!(v0_to_v1(json!([1, 2, 3]), &none()).is_err());
+    }
+
+    #[test]
+    fn v1_to_v2_moves_settings_into_default_profile() {
+        let migrated = v1_to_v2(json!({ "version": 1, "settings": { "positionGrid": 3 } }), &none()).unwrap();
+        assert_eq!(
+            migrated,
+            json!({
+                "version": 2,
+                "activeProfile": "Default",
+                "profiles": { "Default": { "positionGrid": 3 } },
+            })
+        );
+    }
+
+    #[test]
+    fn v1_to_v2_requires_settings() {
+        assert!(v1_to_v2(json!({ "version": 1 }), &none()).is_err());
+    }
+
+    #[test]
+    fn v2_to_v3_replaces_monitor_indices_with_identities() {
+        let context = MigrationContext {
+            monitors: vec![
+                MonitorDescriptor::new("eDP-1", 0, 0, 1920, 1080),
+                MonitorDescriptor::new("DP-1", 1920, 0, 2560, 1440),
+            ],
+        };
+        let migrated = v2_to_v3(
+            json!({
+                "version": 2,
+                "activeProfile": "Default",
+                "profiles": {
+                    "Default": { "preferredMonitor": 1 },
+                    "Desk": { "preferredMonitor": "0" },
+                    "Gone": { "preferredMonitor": 4 },
+                    "Primary": { "preferredMonitor": "primary" },
+                    "Unset": {},
+                },
+            }),
+            &context,
+        )
+        .unwrap();
+        let profiles = &migrated["profiles"];
+        assert_eq!(version_of(&migrated), 3);
+        assert_eq!(profiles["Default"]["preferredMonitor"], json!({ "name": "DP-1", "x": 1920, "y": 0, "width": 2560, "height": 1440 }));
+        assert_eq!(profiles["Desk"]["preferredMonitor"]["name"], json!("eDP-1"));
+        assert_eq!(profiles["Gone"]["preferredMonitor"], json!("auto"));
+        assert_eq!(profiles["Primary"]["preferredMonitor"], json!("primary"));
+        assert_eq!(profiles["Unset"], json!({}));
+    }
+
+    #[test]
+    fn migrate_brings_v0_to_current() {
+        let migrated = migrate(json!({ "manualX": 640, "manualY": 480 }), &none()).unwrap();
+        assert_eq!(version_of(&migrated), CURRENT_VERSION);
+        assert_eq!(migrated["profiles"]["Default"]["manualX"], json!(640));
+        assert_eq!(migrated["profiles"]["Default"]["manualY"], json!(480));
+    }
+
+    #[test]
+    fn migrate_leaves_current_documents_untouched() {
+        let doc = json!({ "version": CURRENT_VERSION, "activeProfile": "Home", "profiles": { "Home": { "fontSize": 24 } } });
+        assert_eq!(migrate(doc.clone(), &none()).unwrap(), doc);
+    }
+
+    #[test]
+    fn migrate_refuses_newer_documents() {
+        let doc = json!({ "version": CURRENT_VERSION + 1, "settings": {} });
+        assert!(migrate(doc, &none()).is_err());
+    }
+
+    #[test]
+    fn every_version_has_a_migration() {
+        assert_eq!(MIGRATIONS.len(), CURRENT_VERSION as usize);
+    }
+}
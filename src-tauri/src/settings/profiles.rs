@@ -0,0 +1,253 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+use super::Settings;
+
+pub const DEFAULT_PROFILE: &str = "Default";
+const MAX_NAME_LENGTH: usize = 40;
+
+/// Named sets of settings (e.g. "Work" and "Home"), one of which is active.
+/// Every field of `Settings` is per profile: appearance, position, monitor
+/// preference, topic and AI provider.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Profiles {
+    active: String,
+    profiles: BTreeMap<String, Settings>,
+    /// Monitor layout fingerprint -> profile to activate when that layout is connected
+    layout_bindings: BTreeMap<String, String>,
+}
+
+/// What the frontend and tray need to render a profile picker.
+#[derive(Serialize, Clone, Debug, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfilesInfo {
+    pub active: String,
+    pub names: Vec<String>,
+}
+
+impl Default for Profiles {
+    fn default() -> Self {
+        Profiles::single(DEFAULT_PROFILE, Settings::default())
+    }
+}
+
+impl Profiles {
+    pub fn single(name: &str, settings: Settings) -> Self {
+        let mut profiles = BTreeMap::new();
+        profiles.insert(name.to_string(), settings);
+        Profiles {
+            active: name.to_string(),
+            profiles,
+            layout_bindings: BTreeMap::new(),
+        }
+    }
+
+    /// Builds profiles from stored data, falling back to the first profile
+    /// (or a fresh default) when the stored active name no longer exists.
+    /// Layout bindings pointing at missing profiles are dropped.
+    pub fn from_parts(active: String, profiles: BTreeMap<String, Settings>, mut layout_bindings: BTreeMap<String, String>) -> Self {
+        if profiles.is_empty() {
+            return Profiles::default();
+        }
+
+        let active = if profiles.contains_key(&active) {
+            active
+        } else {
+            profiles.keys().next().cloned().unwrap_or_default()
+        };
+        layout_bindings.retain(|_, profile| profiles.contains_key(profile));
+
+        Profiles {
+            active,
+            profiles,
+            layout_bindings,
+        }
+    }
+
+    pub fn active_name(&self) -> &str {
+        &self.active
+    }
+
+    pub fn active(&self) -> &Settings {
+        &self.profiles[&self.active]
+    }
+
+    pub fn set_active_settings(&mut self, settings: Settings) {
+        self.profiles.insert(self.active.clone(), settings);
+    }
+
+    pub fn all(&self) -> &BTreeMap<String, Settings> {
+        &self.profiles
+    }
+
+    pub fn layout_bindings(&self) -> &BTreeMap<String, String> {
+        &self.layout_bindings
+    }
+
+    /// The profile bound to a monitor layout fingerprint, if any.
+    pub fn profile_for_layout(&self, fingerprint: &str) -> Option<&str> {
+        self.layout_bindings.get(fingerprint).map(String::as_str)
+    }
+
+    /// Activates `profile` automatically whenever `fingerprint` is connected.
+    /// A layout can only be bound to one profile; rebinding replaces it.
+    pub fn bind_layout(&mut self, fingerprint: &str, profile: &str) -> Result<(), String> {
+        if !self.profiles.contains_key(profile) {
+            return Err(format!("No profile named \"{}\"", profile));
+        }
+        if fingerprint.is_empty() {
+            return Err("Monitor layout fingerprint cannot be empty".to_string());
+        }
+        self.layout_bindings.insert(fingerprint.to_string(), profile.to_string());
+        Ok(())
+    }
+
+    pub fn unbind_layout(&mut self, fingerprint: &str) -> Result<(), String> {
+        self.layout_bindings
+            .remove(fingerprint)
+            .map(|_| ())
+            .ok_or_else(|| "That monitor layout is not bound to a profile".to_string())
+    }
+
+    pub fn info(&self) -> ProfilesInfo {
+        ProfilesInfo {
+            active: self.active.clone(),
+            names: self.profiles.keys().cloned().collect(),
+        }
+    }
+
+    /// Adds a new profile with default settings.
+    pub fn create(&mut self, name: &str) -> Result<String, String> {
+        let name = self.available_name(name)?;
+        self.profiles.insert(name.clone(), Settings::default());
+        Ok(name)
+    }
+
+    /// Adds a new profile starting from a copy of `source`.
+    pub fn clone_profile(&mut self, source: &str, name: &str) -> Result<String, String> {
+        let settings = self
+            .profiles
+            .get(source)
+            .cloned()
+            .ok_or_else(|| format!("No profile named \"{}\"", source))?;
+        let name = self.available_name(name)?;
+        self.profiles.insert(name.clone(), settings);
+        Ok(name)
+    }
+
+    /// Removes a profile. Deleting the active profile activates the first
+    /// remaining one; the last profile can never be deleted.
+    pub fn delete(&mut self, name: &str) -> Result<(), String> {
+        if !self.profiles.contains_key(name) {
+            return Err(format!("No profile named \"{}\"", name));
+        }
+        if self.profiles.len() == 1 {
+            return Err("Cannot delete the only profile".to_string());
+        }
+
+        self.profiles.remove(name);
+        self.layout_bindings.retain(|_, profile| profile != name);
+        if self.active == name {
+            self.active = self.profiles.keys().next().cloned().unwrap_or_default();
+        }
+        Ok(())
+    }
+
+    pub fn switch(&mut self, name: &str) -> Result<(), String> {
+        if !self.profiles.contains_key(name) {
+            return Err(format!("No profile named \"{}\"", name));
+        }
+        self.active = name.to_string();
+        Ok(())
+    }
+
+    fn available_name(&self, name: &str) -> Result<String, String> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err("Profile name cannot be empty".to_string());
+        }
+        if name.chars().count() > MAX_NAME_LENGTH {
+            return Err(format!("Profile name is longer than {} characters", MAX_NAME_LENGTH));
+        }
+        if self.profiles.keys().any(|existing| existing.eq_ignore_ascii_case(name)) {
+            return Err(format!("A profile named \"{}\" already exists", name));
+        }
+        Ok(name.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn work_and_home() -> Profiles {
+        let mut profiles = Profiles::single("Work", Settings { click_through: true, ..Settings::default() });
+        profiles.create("Home").unwrap();
+        profiles
+    }
+
+    #[test]
+    fn create_rejects_blank_and_duplicate_names() {
+        let mut profiles = work_and_home();
+        assert!(profiles.create("   ").is_err());
+        assert!(profiles.create("work").is_err());
+        assert_eq!(profiles.create("  Travel ").unwrap(), "Travel");
+    }
+
+    #[test]
+    fn clone_copies_source_settings() {
+        let mut profiles = work_and_home();
+        profiles.clone_profile("Work", "Office").unwrap();
+        assert!(profiles.all()["Office"].click_through);
+        assert!(profiles.clone_profile("Missing", "Other").is_err());
+    }
+
+    #[test]
+    fn switch_changes_active_settings() {
+        let mut profiles = work_and_home();
+        assert!(profiles.active().click_through);
+        profiles.switch("Home").unwrap();
+        assert_eq!(profiles.active_name(), "Home");
+        assert!(!profiles.active().click_through);
+        assert!(profiles.switch("Missing").is_err());
+    }
+
+    #[test]
+    fn deleting_active_profile_activates_another() {
+        let mut profiles = work_and_home();
+        profiles.delete("Work").unwrap();
+        assert_eq!(profiles.active_name(), "Home");
+        assert!(profiles.delete("Home").is_err());
+    }
+
+    #[test]
+    fn from_parts_repairs_missing_active_profile() {
+        let mut map = BTreeMap::new();
+        map.insert("Home".to_string(), Settings::default());
+        let mut bindings = BTreeMap::new();
+        bindings.insert("laptop".to_string(), "Home".to_string());
+        bindings.insert("dock".to_string(), "Gone".to_string());
+
+        let profiles = Profiles::from_parts("Gone".to_string(), map, bindings);
+        assert_eq!(profiles.active_name(), "Home");
+        assert_eq!(profiles.profile_for_layout("laptop"), Some("Home"));
+        assert_eq!(profiles.profile_for_layout("dock"), None);
+        assert_eq!(Profiles::from_parts("Gone".to_string(), BTreeMap::new(), BTreeMap::new()), Profiles::default());
+    }
+
+    #[test]
+    fn layout_bindings_follow_profiles() {
+        let mut profiles = work_and_home();
+        profiles.bind_layout("dock", "Work").unwrap();
+        profiles.bind_layout("laptop", "Home").unwrap();
+        assert!(profiles.bind_layout("tv", "Missing").is_err());
+        assert_eq!(profiles.profile_for_layout("dock"), Some("Work"));
+
+        profiles.delete("Work").unwrap();
+        assert_eq!(profiles.profile_for_layout("dock"), None);
+        assert_eq!(profiles.profile_for_layout("laptop"), Some("Home"));
+
+        profiles.unbind_layout("laptop").unwrap();
+        assert!(profiles.unbind_layout("laptop").is_err());
+    }
+}
@@ -0,0 +1,456 @@
+//! Global shortcuts. Every action has a rebindable accelerator stored in
+//! the settings; a binding that cannot be registered, e.g. because another
+//! application owns it, is reported to the settings window and skipped
+//! instead of taking the app down with it.
+//!
+//! The optional leader shortcut opens a brief modal window for single-key
+//! grading; see `leader`.
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use tauri::{GlobalShortcutManager, Manager};
+
+use crate::app_state::{self, Grade};
+use crate::settings::SettingsStore;
+
+mod accelerator;
+mod leader;
+
+pub use accelerator::{Accelerator, ShortcutProblem};
+
+/// Something a shortcut can do.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "kebab-case")]
+pub enum Action {
+    NextCard,
+    PreviousCard,
+    RevealCard,
+    GradeAgain,
+    GradeHard,
+    GradeGood,
+    GradeEasy,
+    ToggleClickThrough,
+    TemporaryIcons,
+    ToggleWindow,
+    OpenSettings,
+    PauseRotation,
+    Leader,
+}
+
+impl Action {
+    pub const ALL: [Action; 13] = [
+        Action::NextCard,
+        Action::PreviousCard,
+        Action::RevealCard,
+        Action::GradeAgain,
+        Action::GradeHard,
+        Action::GradeGood,
+        Action::GradeEasy,
+        Action::ToggleClickThrough,
+        Action::TemporaryIcons,
+        Action::ToggleWindow,
+        Action::OpenSettings,
+        Action::PauseRotation,
+        Action::Leader,
+    ];
+
+    /// How the action is named in diagnostics.
+    fn label(self) -> &'static str {
+        match self {
+            Action::NextCard => "Next card",
+            Action::PreviousCard => "Previous card",
+            Action::RevealCard => "Reveal / flip",
+            Action::GradeAgain => "Grade: again",
+            Action::GradeHard => "Grade: hard",
+            Action::GradeGood => "Grade: good",
+            Action::GradeEasy => "Grade: easy",
+            Action::ToggleClickThrough => "Toggle click-through",
+            Action::TemporaryIcons => "Show icons briefly",
+            Action::ToggleWindow => "Hide / show card",
+            Action::OpenSettings => "Open settings",
+            Action::PauseRotation => "Pause / resume",
+            Action::Leader => "Leader key",
+        }
+    }
+
+    /// The accelerator an action starts out with. Grading and the leader are
+    /// left unbound, since bare digit combinations are easily taken by other
+    /// apps and the modal keys are opt-in.
+    fn default_accelerator(self) -> &'static str {
+        match self {
+            Action::NextCard => "CmdOrCtrl+Shift+Right",
+            Action::PreviousCard => "CmdOrCtrl+Shift+Left",
+            Action::RevealCard => "CmdOrCtrl+Shift+Down",
+            Action::GradeAgain | Action::GradeHard | Action::GradeGood | Action::GradeEasy | Action::Leader => "",
+            Action::ToggleClickThrough => "CmdOrCtrl+Shift+K",
+            Action::TemporaryIcons => "CmdOrCtrl+Shift+I",
+            Action::ToggleWindow => "CmdOrCtrl+Shift+H",
+            Action::OpenSettings => "CmdOrCtrl+Shift+Comma",
+            Action::PauseRotation => "CmdOrCtrl+Shift+P",
+        }
+    }
+}
+
+/// Accelerator per action; an empty accelerator leaves the action unbound.
+pub type Keymap = BTreeMap<Action, String>;
+
+pub fn default_keymap() -> Keymap {
+    Action::ALL.iter().map(|action| (*action, action.default_accelerator().to_string())).collect()
+}
+
+/// Fills in actions missing from a stored keymap, e.g. ones added by a newer
+/// version, and trims stray whitespace.
+pub fn completed(mut keymap: Keymap) -> Keymap {
+    for action in Action::ALL {
+        let accelerator = keymap.entry(action).or_insert_with(|| action.default_accelerator().to_string());
+        *accelerator = accelerator.trim().to_string();
+    }
+    keymap
+}
+
+/// A binding that cannot be used, with the reason. Serialized flat, as
+/// `{ "action", "accelerator", "kind", "message" }`.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ShortcutError {
+    pub action: Action,
+    pub accelerator: String,
+    #[serde(flatten)]
+    pub problem: ShortcutProblem,
+}
+
+/// Checks every bound accelerator in `keymap` without registering anything.
+/// Returns the usable bindings in canonical form and the problems with the
+/// rest. Both sides of a duplicate are reported and neither is usable.
+pub fn check(keymap: &Keymap) -> (BTreeMap<Action, Accelerator>, Vec<ShortcutError>) {
+    let mut parsed = BTreeMap::new();
+    let mut errors = Vec::new();
+    for (action, text) in keymap.iter().filter(|(_, text)| !text.is_empty()) {
+        match Accelerator::parse(text) {
+            Ok(accelerator) => {
+                parsed.insert(*action, accelerator);
+            }
+            Err(problem) => errors.push(ShortcutError {
+                action: *action,
+                accelerator: text.clone(),
+                problem,
+            }),
+        }
+    }
+
+    let mut users: HashMap<Accelerator, Vec<Action>> = HashMap::new();
+    for (action, accelerator) in &parsed {
+        users.entry(*accelerator).or_default().push(*action);
+    }
+    for actions in users.values().filter(|actions| actions.len() > 1) {
+        for action in actions {
+            let other = *actions.iter().find(|other| *other != action).unwrap_or(action);
+            errors.push(ShortcutError {
+                action: *action,
+                accelerator: keymap[action].clone(),
+                problem: ShortcutProblem::Duplicate {
+                    message: format!("Also bound to \"{}\"", other.label()),
+                    other,
+                },
+            });
+            parsed.remove(action);
+        }
+    }
+
+    errors.sort_by_key(|error| error.action);
+    (parsed, errors)
+}
+
+/// The registered bindings, managed as Tauri state.
+#[derive(Default)]
+pub struct Shortcuts {
+    registration: Mutex<Registration>,
+    modal: leader::Modal,
+}
+
+#[derive(Default)]
+struct Registration {
+    keymap: Keymap,
+    registered: BTreeMap<Action, Accelerator>,
+    errors: Vec<ShortcutError>,
+}
+
+impl Registration {
+    fn register(&mut self, app_handle: &tauri::AppHandle, action: Action, accelerator: Accelerator) -> Result<(), ShortcutError> {
+        let app = app_handle.clone();
+        app_handle
+            .global_shortcut_manager()
+            .register(&accelerator.to_string(), move || dispatch(&app, action))
+            .map_err(|e| ShortcutError {
+                action,
+                accelerator: self.keymap.get(&action).cloned().unwrap_or_else(|| accelerator.to_string()),
+                problem: ShortcutProblem::Unavailable {
+                    message: format!("Could not be registered, another app may be using it ({})", e),
+                },
+            })?;
+        self.registered.insert(action, accelerator);
+        Ok(())
+    }
+
+    /// Tries the bindings that another app was holding again. Returns
+    /// whether any of them could be registered this time.
+    fn retry_unavailable(&mut self, app_handle: &tauri::AppHandle) -> bool {
+        let is_unavailable = |error: &ShortcutError| matches!(error.problem, ShortcutProblem::Unavailable { .. });
+        let unavailable: Vec<Action> = self.errors.iter().filter(|error| is_unavailable(error)).map(|error| error.action).collect();
+        if unavailable.is_empty() {
+            return false;
+        }
+
+        let (parsed, _) = check(&self.keymap);
+        let mut recovered = false;
+        for action in unavailable {
+            let Some(accelerator) = parsed.get(&action).copied() else {
+                continue;
+            };
+            if self.register(app_handle, action, accelerator).is_ok() {
+                println!("⌨️ Shortcut {} for {:?} is available again", accelerator, action);
+                self.errors.retain(|error| error.action != action || !is_unavailable(error));
+                recovered = true;
+            }
+        }
+        recovered
+    }
+
+    /// Registers `previous` for `action` again after a rebind was undone.
+    fn restore(&mut self, app_handle: &tauri::AppHandle, action: Action, previous: Option<Accelerator>) {
+        if let Some(previous) = previous {
+            if let Err(e) = self.register(app_handle, action, previous) {
+                println!("⚠️ Failed to restore shortcut {}: {}", previous, e.problem);
+            }
+        }
+    }
+
+    fn unregister(&mut self, app_handle: &tauri::AppHandle, action: Action) {
+        if let Some(accelerator) = self.registered.remove(&action) {
+            if let Err(e) = app_handle.global_shortcut_manager().unregister(&accelerator.to_string()) {
+                println!("⚠️ Failed to unregister shortcut {}: {}", accelerator, e);
+            }
+        }
+    }
+}
+
+/// Replaces the registered shortcuts with `keymap`. Bindings that are
+/// invalid, duplicated or taken are kept in the errors and broadcast as
+/// `shortcut-errors`; the rest work. Taken bindings are tried again on every
+/// call, even when the keymap is unchanged, since the app holding them may
+/// have let go.
+pub fn apply(app_handle: &tauri::AppHandle, keymap: &Keymap) {
+    let shortcuts = app_handle.state::<Shortcuts>();
+    let mut registration = crate::lock(&shortcuts.registration);
+    if registration.keymap == *keymap {
+        if registration.retry_unavailable(app_handle) {
+            let _ = app_handle.emit_all("shortcut-errors", &registration.errors);
+        }
+        return;
+    }
+
+    let actions: Vec<Action> = registration.registered.keys().copied().collect();
+    for action in actions {
+        registration.unregister(app_handle, action);
+    }
+
+    println!("⌨️ Registering global shortcuts:");
+    registration.keymap = keymap.clone();
+    let (parsed, mut errors) = check(keymap);
+    for (action, accelerator) in parsed {
+        match registration.register(app_handle, action, accelerator) {
+            Ok(()) => println!("  - {} ({:?})", accelerator, action),
+            Err(error) => errors.push(error),
+        }
+    }
+    for error in &errors {
+        println!("⚠️ Shortcut {} for {:?}: {}", error.accelerator, error.action, error.problem);
+    }
+
+    registration.errors = errors;
+    let _ = app_handle.emit_all("shortcut-errors", &registration.errors);
+}
+
+/// Bindings that failed the last time the keymap was applied.
+#[tauri::command]
+pub fn get_shortcut_errors(shortcuts: tauri::State<Shortcuts>) -> Vec<ShortcutError> {
+    crate::lock(&shortcuts.registration).errors.clone()
+}
+
+/// Parses an accelerator and returns it in canonical form, e.g. to show
+/// what `CmdOrCtrl` means on this platform.
+#[tauri::command]
+pub fn parse_accelerator(accelerator: String) -> Result<String, ShortcutProblem> {
+    Accelerator::parse(&accelerator).map(|accelerator| accelerator.to_string())
+}
+
+/// Checks a whole keymap, e.g. one being edited, without registering it.
+#[tauri::command]
+pub fn check_keymap(keymap: Keymap) -> Vec<ShortcutError> {
+    check(&completed(keymap)).1
+}
+
+/// Binds `action` to `accelerator`, or unbinds it when empty. The new
+/// binding is registered before it is saved; if registering or saving fails
+/// the old one is restored and the reasons are returned instead.
+#[tauri::command]
+pub fn rebind_shortcut(
+    app_handle: tauri::AppHandle,
+    shortcuts: tauri::State<Shortcuts>,
+    store: tauri::State<SettingsStore>,
+    action: Action,
+    accelerator: String,
+) -> Result<Keymap, Vec<ShortcutError>> {
+    let mut keymap = store.get().shortcuts;
+    keymap.insert(action, accelerator.trim().to_string());
+    let (parsed, errors) = check(&keymap);
+    let errors: Vec<ShortcutError> = errors.into_iter().filter(|error| error.action == action).collect();
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    let (previous, previous_keymap, previous_errors) = {
+        let mut registration = crate::lock(&shortcuts.registration);
+        let previous = registration.registered.get(&action).copied();
+        registration.unregister(&app_handle, action);
+        if let Some(accelerator) = parsed.get(&action) {
+            if let Err(error) = registration.register(&app_handle, action, *accelerator) {
+                // Roll back to the binding that worked
+                registration.restore(&app_handle, action, previous);
+                return Err(vec![error]);
+            }
+        }
+        let previous_keymap = std::mem::replace(&mut registration.keymap, keymap.clone());
+        let previous_errors = registration.errors.clone();
+        registration.errors.retain(|error| error.action != action);
+        let _ = app_handle.emit_all("shortcut-errors", &registration.errors);
+        (previous, previous_keymap, previous_errors)
+    };
+
+    match store.update(&app_handle, &json!({ "shortcuts": keymap })) {
+        Ok(saved) => Ok(saved.shortcuts),
+        Err(e) => {
+            // The settings still hold the old binding, so that is the one that should work
+            let mut registration = crate::lock(&shortcuts.registration);
+            registration.unregister(&app_handle, action);
+            registration.restore(&app_handle, action, previous);
+            registration.keymap = previous_keymap;
+            registration.errors = previous_errors;
+            let _ = app_handle.emit_all("shortcut-errors", &registration.errors);
+            Err(vec![ShortcutError {
+                action,
+                accelerator: accelerator.clone(),
+                problem: ShortcutProblem::Unavailable { message: e },
+            }])
+        }
+    }
+}
+
+/// Runs `action`. Card actions are carried out by the main window.
+pub fn dispatch(app_handle: &tauri::AppHandle, action: Action) {
+    println!("⌨️ Shortcut: {:?}", action);
+    match action {
+        Action::NextCard => {
+            let _ = app_handle.emit_to("main", "next-card", ());
+        }
+        Action::PreviousCard => {
+            let _ = app_handle.emit_to("main", "previous-card", ());
+        }
+        Action::RevealCard => {
+            let _ = app_handle.emit_to("main", "reveal-card", ());
+        }
+        Action::GradeAgain => app_state::grade_card(app_handle, Grade::Again),
+        Action::GradeHard => app_state::grade_card(app_handle, Grade::Hard),
+        Action::GradeGood => app_state::grade_card(app_handle, Grade::Good),
+        Action::GradeEasy => app_state::grade_card(app_handle, Grade::Easy),
+        Action::ToggleClickThrough => {
+            crate::toggle_click_through(app_handle.clone(), app_handle.state());
+        }
+        Action::TemporaryIcons => {
+            crate::enable_temporary_icons(app_handle.clone(), app_handle.state(), app_handle.state());
+        }
+        Action::ToggleWindow => crate::toggle_main_window(app_handle),
+        Action::OpenSettings => crate::show_settings_window(app_handle.clone(), app_handle.state()),
+        Action::PauseRotation => app_state::toggle_paused(app_handle),
+        Action::Leader => leader::toggle(app_handle),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_action_has_a_default_entry() {
+        let keymap = default_keymap();
+        assert_eq!(keymap.len(), Action::ALL.len());
+        assert_eq!(keymap[&Action::TemporaryIcons], "CmdOrCtrl+Shift+I");
+        assert_eq!(keymap[&Action::GradeGood], "");
+    }
+
+    #[test]
+    fn completed_keeps_rebindings_and_fills_gaps() {
+        let stored: Keymap = serde_json::from_value(json!({ "next-card": " Alt+N ", "grade-good": "" })).unwrap();
+        let keymap = completed(stored);
+        assert_eq!(keymap[&Action::NextCard], "Alt+N");
+        assert_eq!(keymap[&Action::GradeGood], "");
+        assert_eq!(keymap[&Action::OpenSettings], "CmdOrCtrl+Shift+Comma");
+        assert_eq!(keymap.len(), Action::ALL.len());
+    }
+
+    #[test]
+    fn check_reports_both_sides_of_a_duplicate() {
+        let mut keymap = default_keymap();
+        keymap.insert(Action::GradeGood, "Ctrl+Shift+K".to_string());
+        keymap.insert(Action::GradeEasy, "Ctrl+Nope".to_string());
+        let (parsed, errors) = check(&keymap);
+
+        let kinds: Vec<(Action, &str)> = errors
+            .iter()
+            .map(|error| {
+                let kind = match error.problem {
+                    ShortcutProblem::Duplicate { .. } => "duplicate",
+                    ShortcutProblem::UnknownKey { .. } => "unknown",
+                    _ => "other",
+                };
+                (error.action, kind)
+            })
+            .collect();
+        if cfg!(target_os = "macos") {
+            // CmdOrCtrl is Cmd there, so the two do not clash
+            assert_eq!(kinds, vec![(Action::GradeEasy, "unknown")]);
+        } else {
+            assert_eq!(
+                kinds,
+                vec![(Action::GradeGood, "duplicate"), (Action::GradeEasy, "unknown"), (Action::ToggleClickThrough, "duplicate")]
+            );
+            assert!(!parsed.contains_key(&Action::ToggleClickThrough));
+        }
+        assert!(parsed.contains_key(&Action::NextCard));
+    }
+
+    #[test]
+    fn errors_serialize_flat() {
+        let error = ShortcutError {
+            action: Action::NextCard,
+            accelerator: "Ctrl+Nope".to_string(),
+            problem: ShortcutProblem::UnknownKey { message: "nope".to_string() },
+        };
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            json!({ "action": "next-card", "accelerator": "Ctrl+Nope", "kind": "unknownKey", "message": "nope" })
+        );
+    }
+
+    #[test]
+    fn default_keymap_is_valid() {
+        assert!(check(&default_keymap()).1.is_empty());
+    }
+
+    #[test]
+    fn unknown_actions_are_rejected() {
+        assert!(serde_json::from_value::<Keymap>(json!({ "launch-rockets": "Ctrl+R" })).is_err());
+    }
+}
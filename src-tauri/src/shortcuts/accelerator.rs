@@ -0,0 +1,243 @@
+//! Parsing of accelerator strings such as `CmdOrCtrl+Shift+K`. Tauri accepts
+//! the same syntax but quietly drops keys it does not know, so bindings are
+//! checked here first and explained when they are wrong.
+
+use serde::Serialize;
+use std::fmt;
+
+/// Why a binding cannot be used. Serialized for the frontend as
+/// `{ "kind": "unknownKey", "message": "..." }`.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum ShortcutProblem {
+    InvalidSyntax { message: String },
+    UnknownKey { message: String },
+    MissingModifier { message: String },
+    Duplicate { message: String, other: super::Action },
+    Unavailable { message: String },
+}
+
+impl ShortcutProblem {
+    fn invalid_syntax(message: impl Into<String>) -> Self {
+        ShortcutProblem::InvalidSyntax { message: message.into() }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            ShortcutProblem::InvalidSyntax { message }
+            | ShortcutProblem::UnknownKey { message }
+            | ShortcutProblem::MissingModifier { message }
+            | ShortcutProblem::Duplicate { message, .. }
+            | ShortcutProblem::Unavailable { message } => message,
+        }
+    }
+}
+
+impl fmt::Display for ShortcutProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message())
+    }
+}
+
+/// A parsed accelerator. `CmdOrCtrl` is resolved for the current platform,
+/// so two spellings of the same keys compare equal.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Accelerator {
+    pub ctrl: bool,
+    pub alt: bool,
+    pub shift: bool,
+    pub meta: bool,
+    pub key: &'static str,
+}
+
+/// Named keys with the spellings Tauri accepts for them, canonical first.
+/// The canonical spelling is what gets saved and registered, so it has to be
+/// one Tauri's own parser knows, even where a word would read better.
+const NAMED_KEYS: &[&[&str]] = &[
+    &["Space"],
+    &["Enter"],
+    &["Tab"],
+    &["Backspace"],
+    &["Delete"],
+    &["Insert"],
+    &["Escape", "Esc"],
+    &["Home"],
+    &["End"],
+    &["PageUp"],
+    &["PageDown"],
+    &["Up", "ArrowUp"],
+    &["Down", "ArrowDown"],
+    &["Left", "ArrowLeft"],
+    &["Right", "ArrowRight"],
+    &["Comma", ","],
+    &["Period", "."],
+    &["Slash", "/"],
+    &["Semicolon", ";"],
+    &["Quote", "'"],
+    &["Backquote", "`"],
+    &["BracketLeft", "["],
+    &["BracketRight", "]"],
+    &["Backslash"],
+    &["-", "Minus"],
+    &["=", "Equal"],
+    &["Plus"],
+    &["PrintScreen"],
+    &["ScrollLock"],
+    &["Pause"],
+    &["VolumeMute"],
+    &["VolumeDown"],
+    &["VolumeUp"],
+    &["MediaNextTrack"],
+    &["MediaPreviousTrack"],
+    &["MediaPlayPause"],
+];
+
+const LETTERS_AND_DIGITS: [&str; 36] = [
+    "A", "B", "C", "D", "E", "F", "G", "H", "I", "J", "K", "L", "M", "N", "O", "P", "Q", "R", "S", "T", "U", "V", "W", "X", "Y", "Z",
+    "0", "1", "2", "3", "4", "5", "6", "7", "8", "9",
+];
+
+const FUNCTION_KEYS: [&str; 24] = [
+    "F1", "F2", "F3", "F4", "F5", "F6", "F7", "F8", "F9", "F10", "F11", "F12", "F13", "F14", "F15", "F16", "F17", "F18", "F19", "F20",
+    "F21", "F22", "F23", "F24",
+];
+
+/// The canonical name of `token` as a main key.
+fn key_name(token: &str) -> Option<&'static str> {
+    let upper = token.to_uppercase();
+    LETTERS_AND_DIGITS
+        .iter()
+        .chain(FUNCTION_KEYS.iter())
+        .find(|name| **name == upper)
+        .copied()
+        .or_else(|| {
+            NAMED_KEYS
+                .iter()
+                .find(|spellings| spellings.iter().any(|spelling| spelling.to_uppercase() == upper))
+                .map(|spellings| spellings[0])
+        })
+}
+
+/// Keys that type nothing, so they may be bound without a modifier.
+fn is_standalone(key: &str) -> bool {
+    FUNCTION_KEYS.contains(&key) || key.starts_with("Volume") || key.starts_with("Media")
+}
+
+impl Accelerator {
+    pub fn parse(text: &str) -> Result<Accelerator, ShortcutProblem> {
+        let mut accelerator = Accelerator {
+            ctrl: false,
+            alt: false,
+            shift: false,
+            meta: false,
+            key: "",
+        };
+
+        for token in text.split('+').map(str::trim) {
+            if token.is_empty() {
+                return Err(ShortcutProblem::invalid_syntax(format!("\"{}\" has an empty part; write Plus for the + key", text)));
+            }
+            if !accelerator.key.is_empty() {
+                return Err(ShortcutProblem::invalid_syntax(format!(
+                    "\"{}\" must end with exactly one key, after the modifiers",
+                    text
+                )));
+            }
+            match token.to_uppercase().as_str() {
+                "CONTROL" | "CTRL" => accelerator.ctrl = true,
+                "OPTION" | "ALT" => accelerator.alt = true,
+                "SHIFT" => accelerator.shift = true,
+                "COMMAND" | "CMD" | "SUPER" => accelerator.meta = true,
+                "COMMANDORCONTROL" | "COMMANDORCTRL" | "CMDORCTRL" | "CMDORCONTROL" => {
+                    if cfg!(target_os = "macos") {
+                        accelerator.meta = true;
+                    } else {
+                        accelerator.ctrl = true;
+                    }
+                }
+                _ => {
+                    accelerator.key = key_name(token).ok_or_else(|| ShortcutProblem::UnknownKey {
+                        message: format!("\"{}\" is not a key that can be used in a shortcut", token),
+                    })?;
+                }
+            }
+        }
+
+        if accelerator.key.is_empty() {
+            return Err(ShortcutProblem::invalid_syntax(format!("\"{}\" has modifiers but no key", text)));
+        }
+        // A global shortcut swallows its keys everywhere, so it must not be plain typing
+        let only_shift = !(accelerator.ctrl || accelerator.alt || accelerator.meta);
+        if only_shift && !is_standalone(accelerator.key) {
+            return Err(ShortcutProblem::MissingModifier {
+                message: format!("\"{}\" needs Ctrl, Alt or Cmd, or it would block typing in other apps", text),
+            });
+        }
+        Ok(accelerator)
+    }
+}
+
+impl fmt::Display for Accelerator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let modifiers = [(self.ctrl, "Ctrl"), (self.alt, "Alt"), (self.shift, "Shift"), (self.meta, "Super")];
+        for (_, name) in modifiers.iter().filter(|(held, _)| *held) {
+            write!(f, "{}+", name)?;
+        }
+        write!(f, "{}", self.key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_modifiers_in_any_case() {
+        let accelerator = Accelerator::parse("ctrl+ALT+shift+k").unwrap();
+        assert_eq!(accelerator.to_string(), "Ctrl+Alt+Shift+K");
+    }
+
+    #[test]
+    fn aliases_share_one_canonical_form() {
+        assert_eq!(Accelerator::parse("Ctrl+Shift+ArrowLeft"), Accelerator::parse("Control + Shift + Left"));
+        assert_eq!(Accelerator::parse("Alt+,").unwrap().to_string(), "Alt+Comma");
+        assert_eq!(Accelerator::parse("Option+Esc").unwrap().key, "Escape");
+        assert_eq!(Accelerator::parse("Ctrl+Minus").unwrap().to_string(), "Ctrl+-");
+    }
+
+    #[test]
+    fn canonical_forms_are_accepted_by_tauri() {
+        use std::str::FromStr;
+        let spellings = NAMED_KEYS
+            .iter()
+            .flat_map(|spellings| spellings.iter())
+            .chain(LETTERS_AND_DIGITS.iter())
+            .chain(FUNCTION_KEYS.iter());
+        for spelling in spellings {
+            let canonical = Accelerator::parse(&format!("Ctrl+Shift+{}", spelling)).unwrap().to_string();
+            assert!(tao::accelerator::Accelerator::from_str(&canonical).is_ok(), "{} is saved as {}, which Tauri rejects", spelling, canonical);
+        }
+    }
+
+    #[test]
+    fn cmd_or_ctrl_follows_the_platform() {
+        let expected = if cfg!(target_os = "macos") { "Super+P" } else { "Ctrl+P" };
+        assert_eq!(Accelerator::parse("CmdOrCtrl+P").unwrap().to_string(), expected);
+    }
+
+    #[test]
+    fn rejects_malformed_accelerators() {
+        assert!(matches!(Accelerator::parse("Ctrl++"), Err(ShortcutProblem::InvalidSyntax { .. })));
+        assert!(matches!(Accelerator::parse("Ctrl+K+Shift"), Err(ShortcutProblem::InvalidSyntax { .. })));
+        assert!(matches!(Accelerator::parse("Ctrl+Shift"), Err(ShortcutProblem::InvalidSyntax { .. })));
+        assert!(matches!(Accelerator::parse("Ctrl+Foo"), Err(ShortcutProblem::UnknownKey { .. })));
+    }
+
+    #[test]
+    fn typing_keys_need_a_modifier() {
+        assert!(matches!(Accelerator::parse("K"), Err(ShortcutProblem::MissingModifier { .. })));
+        assert!(matches!(Accelerator::parse("Shift+K"), Err(ShortcutProblem::MissingModifier { .. })));
+        assert!(Accelerator::parse("F7").is_ok());
+        assert!(Accelerator::parse("MediaPlayPause").is_ok());
+    }
+}
@@ -0,0 +1,123 @@
+//! The leader shortcut. Pressing it arms a short modal window in which
+//! single keys grade, flip or skip the card; the keys are only captured
+//! while the window is open, so they type normally the rest of the time.
+//!
+//! Tauri runs shortcut handlers with its listener table locked, so keys are
+//! never registered or unregistered from a handler directly; that always
+//! happens on a thread of its own.
+
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{GlobalShortcutManager, Manager};
+
+use super::{dispatch, Action, Shortcuts};
+use crate::timers::Timers;
+
+/// How long the mode waits for a key before disarming.
+pub const LEADER_TIMEOUT: Duration = Duration::from_millis(1500);
+const LEADER_TIMER: &str = "leader";
+
+/// The keys captured while armed and what they do. Escape only disarms.
+pub const MODAL_KEYS: [(&str, Option<Action>); 7] = [
+    ("1", Some(Action::GradeAgain)),
+    ("2", Some(Action::GradeHard)),
+    ("3", Some(Action::GradeGood)),
+    ("4", Some(Action::GradeEasy)),
+    ("Space", Some(Action::RevealCard)),
+    ("N", Some(Action::NextCard)),
+    ("Escape", None),
+];
+
+/// The modal keys currently registered; empty while disarmed.
+#[derive(Default)]
+pub struct Modal {
+    keys: Mutex<Vec<&'static str>>,
+}
+
+/// Arms the mode, or disarms it when the leader is pressed again.
+pub fn toggle(app_handle: &tauri::AppHandle) {
+    let app = app_handle.clone();
+    std::thread::spawn(move || {
+        let shortcuts = app.state::<Shortcuts>();
+        let mut keys = crate::lock(&shortcuts.modal.keys);
+        if keys.is_empty() {
+            arm(&app, &mut keys);
+        } else {
+            release(&app, &mut keys);
+        }
+    });
+}
+
+fn arm(app_handle: &tauri::AppHandle, keys: &mut Vec<&'static str>) {
+    let mut manager = app_handle.global_shortcut_manager();
+    for (key, action) in MODAL_KEYS {
+        let app = app_handle.clone();
+        let registered = manager.register(key, move || {
+            if let Some(action) = action {
+                dispatch(&app, action);
+            }
+            // One key per leader press
+            let app = app.clone();
+            std::thread::spawn(move || disarm(&app));
+        });
+        match registered {
+            Ok(()) => keys.push(key),
+            Err(e) => println!("⚠️ Leader key {} unavailable: {}", key, e),
+        }
+    }
+    if keys.is_empty() {
+        return;
+    }
+
+    println!("⌨️ Leader armed for {:?}", LEADER_TIMEOUT);
+    let app = app_handle.clone();
+    app_handle.state::<Timers>().once(LEADER_TIMER, LEADER_TIMEOUT, move || {
+        std::thread::spawn(move || disarm(&app));
+    });
+    let _ = app_handle.emit_all("leader-mode", true);
+}
+
+/// Releases the modal keys. Does nothing when the mode is not armed.
+pub fn disarm(app_handle: &tauri::AppHandle) {
+    let shortcuts = app_handle.state::<Shortcuts>();
+    let mut keys = crate::lock(&shortcuts.modal.keys);
+    release(app_handle, &mut keys);
+}
+
+fn release(app_handle: &tauri::AppHandle, keys: &mut Vec<&'static str>) {
+    if keys.is_empty() {
+        return;
+    }
+
+    app_handle.state::<Timers>().cancel(LEADER_TIMER);
+    let mut manager = app_handle.global_shortcut_manager();
+    for key in keys.drain(..) {
+        if let Err(e) = manager.unregister(key) {
+            println!("⚠️ Failed to release leader key {}: {}", key, e);
+        }
+    }
+    println!("⌨️ Leader disarmed");
+    let _ = app_handle.emit_all("leader-mode", false);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shortcuts::Accelerator;
+
+    #[test]
+    fn modal_keys_are_known_to_the_parser() {
+        for (key, _) in MODAL_KEYS {
+            let canonical = Accelerator::parse(&format!("Ctrl+{}", key)).unwrap();
+            assert_eq!(canonical.key, key);
+        }
+    }
+
+    #[test]
+    fn modal_keys_cannot_clash_with_bindings() {
+        // Bindings without a modifier are refused, so bare modal keys are free
+        for (key, _) in MODAL_KEYS {
+            assert!(Accelerator::parse(key).is_err(), "{} could be bound on its own", key);
+        }
+    }
+}
@@ -0,0 +1,291 @@
+//! Snapping for the dragged card. When a drag ends the card is pulled onto
+//! nearby monitor edges, corners and grid slots, and optionally docked
+//! against the other Floatlearn windows. A snapped card is saved as an
+//! anchor so it stays on its edge when the card or monitor changes size.
+//!
+//! The drag itself is run by the window manager. The frontend reports the
+//! mouse button going up through `drag_ended`; as window managers do not
+//! always hand that event back to the page, a window that has stopped moving
+//! for a moment also counts as dropped.
+
+use serde_json::json;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{Manager, PhysicalPosition};
+
+use crate::layout::{self, Point, Rect, Size};
+use crate::monitor_layout::MonitorDescriptor;
+use crate::settings::{Settings, SettingsStore};
+use crate::timers::Timers;
+use crate::window_motion::{Motion, WindowMotion};
+use crate::work_area::WorkAreas;
+
+/// How long the window has to stay still before a drag counts as finished.
+const SETTLE_TIME: Duration = Duration::from_millis(400);
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+const DRAG_TIMER: &str = "drag";
+
+/// Where a window dropped at `window` should snap to, or `None` when nothing
+/// is within `threshold` logical pixels.
+///
+/// Each axis snaps on its own to the edges of `area` and, for windows it
+/// lines up with, to the sides of `others`, so corners come from snapping
+/// both axes. Grid `slots` are only considered when no edge is in reach.
+pub fn snap_position(area: Rect, window: Rect, slots: &[Point], others: &[Rect], threshold: f64) -> Option<Point> {
+    let size = window.size();
+    let nearest = |value: f64, candidates: Vec<f64>| {
+        candidates
+            .into_iter()
+            .filter(|candidate| (candidate - value).abs() <= threshold)
+            .min_by(|a, b| (a - value).abs().total_cmp(&(b - value).abs()))
+    };
+
+    let mut xs = vec![area.x, area.right() - size.width];
+    let mut ys = vec![area.y, area.bottom() - size.height];
+    for other in others {
+        let beside = other.y < window.bottom() + threshold && other.bottom() > window.y - threshold;
+        if beside {
+            xs.extend([other.right(), other.x - size.width, other.x, other.right() - size.width]);
+        }
+        let above_or_below = other.x < window.right() + threshold && other.right() > window.x - threshold;
+        if above_or_below {
+            ys.extend([other.bottom(), other.y - size.height, other.y, other.bottom() - size.height]);
+        }
+    }
+
+    let snapped_x = nearest(window.x, xs);
+    let snapped_y = nearest(window.y, ys);
+    let snapped = if snapped_x.is_some() || snapped_y.is_some() {
+        Point::new(snapped_x.unwrap_or(window.x), snapped_y.unwrap_or(window.y))
+    } else {
+        let distance = |slot: &Point| (slot.x - window.x).hypot(slot.y - window.y);
+        *slots
+            .iter()
+            .filter(|slot| (slot.x - window.x).abs() <= threshold && (slot.y - window.y).abs() <= threshold)
+            .min_by(|a, b| distance(a).total_cmp(&distance(b)))?
+    };
+    Some(layout::clamp_into(area, size, snapped))
+}
+
+/// The drag in progress, managed as Tauri state.
+#[derive(Default)]
+pub struct DragTracker {
+    drag: Mutex<Option<Drag>>,
+}
+
+struct Drag {
+    start: PhysicalPosition<i32>,
+    last_moved: Instant,
+}
+
+/// Starts watching a drag of the main window for its end.
+pub fn drag_started(app_handle: &tauri::AppHandle) {
+    let Some(start) = app_handle.get_window("main").and_then(|window| window.outer_position().ok()) else {
+        return;
+    };
+
+    let tracker = app_handle.state::<DragTracker>();
+    let already_watching = crate::lock(&tracker.drag)
+        .replace(Drag {
+            start,
+            last_moved: Instant::now(),
+        })
+        .is_some();
+    if already_watching {
+        return;
+    }
+
+    let app = app_handle.clone();
+    app_handle.state::<Timers>().repeat(DRAG_TIMER, POLL_INTERVAL, move || {
+        let tracker = app.state::<DragTracker>();
+        let settled = {
+            let mut drag = crate::lock(&tracker.drag);
+            match drag.as_ref() {
+                Some(current) if current.last_moved.elapsed() < SETTLE_TIME => return,
+                Some(_) => drag.take(),
+                // Ended by `drag_ended`
+                None => None,
+            }
+        };
+        app.state::<Timers>().cancel(DRAG_TIMER);
+        if let Some(drag) = settled {
+            let app = app.clone();
+            std::thread::spawn(move || drag_finished(&app, drag.start));
+        }
+    });
+}
+
+/// Called by the frontend when the mouse button is released after a drag,
+/// which ends it without waiting for the window to settle.
+#[tauri::command]
+pub fn drag_ended(app_handle: tauri::AppHandle) {
+    let Some(drag) = crate::lock(&app_handle.state::<DragTracker>().drag).take() else {
+        // Already finished by the settle timeout
+        return;
+    };
+    // The settle timer stops itself once it finds the drag gone
+    // Moving the card can animate, so keep it off the command thread
+    std::thread::spawn(move || drag_finished(&app_handle, drag.start));
+}
+
+/// Whether the user is dragging the main window right now.
+pub fn is_dragging(app_handle: &tauri::AppHandle) -> bool {
+    crate::lock(&app_handle.state::<DragTracker>().drag).is_some()
+}
+
+/// Notes that the main window moved, which keeps a drag going.
+pub fn window_moved(app_handle: &tauri::AppHandle) {
+    if let Some(drag) = crate::lock(&app_handle.state::<DragTracker>().drag).as_mut() {
+        drag.last_moved = Instant::now();
+    }
+}
+
+/// Snaps the dropped card and saves where it ended up.
+fn drag_finished(app_handle: &tauri::AppHandle, start: PhysicalPosition<i32>) {
+    let Some(main_window) = app_handle.get_window("main") else {
+        return;
+    };
+    let Ok(end) = main_window.outer_position() else {
+        return;
+    };
+    if end == start {
+        return;
+    }
+
+    let store = app_handle.state::<SettingsStore>();
+    let settings = store.get();
+    let patch = if settings.snap_on_drop {
+        snap_dropped_window(app_handle, &main_window, &settings, end)
+    } else {
+        None
+    };
+    let patch = patch.unwrap_or_else(|| {
+        println!("✋ Card dropped at ({}, {})", end.x, end.y);
+        json!({ "manualPosition": true, "manualX": end.x, "manualY": end.y, "anchorPosition": false })
+    });
+
+    if let Err(e) = store.update(app_handle, &patch) {
+        println!("⚠️ Failed to save dropped card position: {}", e);
+    }
+}
+
+/// Moves the dropped window onto whatever it snaps to and returns the
+/// settings that pin it there, or `None` when it does not snap.
+fn snap_dropped_window(
+    app_handle: &tauri::AppHandle,
+    main_window: &tauri::Window,
+    settings: &Settings,
+    end: PhysicalPosition<i32>,
+) -> Option<serde_json::Value> {
+    let dropped_on = main_window.current_monitor().ok()??;
+    let monitor = crate::layout_monitor(&dropped_on, &WorkAreas::current());
+    let size = crate::logical_window_size(main_window)?;
+    let area = monitor.logical_area();
+    let to_local = |position: PhysicalPosition<i32>| {
+        Point::new(
+            (f64::from(position.x) - monitor.bounds.x) / monitor.scale_factor,
+            (f64::from(position.y) - monitor.bounds.y) / monitor.scale_factor,
+        )
+    };
+    let dropped = to_local(end);
+    let window = Rect::new(dropped.x, dropped.y, size.width, size.height);
+
+    let grid = layout::grid_dimensions(area.size(), settings.auto_detect_grid, settings.manual_grid_cols, settings.manual_grid_rows);
+    let slots = layout::grid_positions(area, size, grid);
+    let others: Vec<Rect> = if settings.snap_to_windows {
+        app_handle
+            .windows()
+            .values()
+            .filter(|other| other.label() != "main" && other.is_visible().unwrap_or(false))
+            .filter_map(|other| {
+                let position = to_local(other.outer_position().ok()?);
+                let size = other.outer_size().ok()?;
+                let size = Size::new(f64::from(size.width) / monitor.scale_factor, f64::from(size.height) / monitor.scale_factor);
+                Some(Rect::new(position.x, position.y, size.width, size.height))
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    let snapped = snap_position(area, window, &slots, &others, settings.snap_threshold)?;
+    let (anchor, offset) = layout::anchor_for(area, Rect::new(snapped.x, snapped.y, size.width, size.height));
+    println!("🧲 Snapped card to {:?} with offset ({}, {})", anchor, offset.x, offset.y);
+
+    let target = monitor.to_global(snapped);
+    let target = PhysicalPosition::new(target.x.round() as i32, target.y.round() as i32);
+    crate::dodge::clear(app_handle);
+    if let Err(e) = app_handle.state::<WindowMotion>().move_to(main_window, target, Motion::from_settings(settings)) {
+        println!("⚠️ Failed to move snapped card: {}", e);
+    }
+
+    let mut patch = json!({
+        "anchorPosition": true,
+        "anchor": anchor,
+        "anchorOffsetX": offset.x,
+        "anchorOffsetY": offset.y,
+        "anchorOffsetUnit": "px",
+        "manualPosition": false,
+    });
+    // The anchor is relative to the monitor it was dropped on
+    let preferred = crate::target_monitor(main_window, &settings.preferred_monitor).map(|preferred| preferred.bounds);
+    if preferred != Some(monitor.bounds) {
+        patch["preferredMonitor"] = json!(MonitorDescriptor::from_monitor(&dropped_on));
+    }
+    Some(patch)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AREA: Rect = Rect { x: 0.0, y: 0.0, width: 1920.0, height: 1080.0 };
+    const THRESHOLD: f64 = 24.0;
+
+    fn card(x: f64, y: f64) -> Rect {
+        Rect::new(x, y, 400.0, 300.0)
+    }
+
+    #[test]
+    fn snaps_to_nearby_corners() {
+        assert_eq!(snap_position(AREA, card(10.0, 15.0), &[], &[], THRESHOLD), Some(Point::new(0.0, 0.0)));
+        assert_eq!(snap_position(AREA, card(1510.0, 790.0), &[], &[], THRESHOLD), Some(Point::new(1520.0, 780.0)));
+    }
+
+    #[test]
+    fn snaps_one_axis_to_an_edge() {
+        assert_eq!(snap_position(AREA, card(600.0, 1.0), &[], &[], THRESHOLD), Some(Point::new(600.0, 0.0)));
+    }
+
+    #[test]
+    fn far_from_everything_does_not_snap() {
+        let slots = [Point::new(100.0, 100.0)];
+        assert_eq!(snap_position(AREA, card(600.0, 400.0), &slots, &[], THRESHOLD), None);
+    }
+
+    #[test]
+    fn snaps_to_the_closest_grid_slot() {
+        let slots = [Point::new(500.0, 300.0), Point::new(520.0, 310.0)];
+        assert_eq!(snap_position(AREA, card(515.0, 305.0), &slots, &[], THRESHOLD), Some(Point::new(520.0, 310.0)));
+    }
+
+    #[test]
+    fn edges_win_over_grid_slots() {
+        let slots = [Point::new(40.0, 40.0)];
+        assert_eq!(snap_position(AREA, card(20.0, 50.0), &slots, &[], THRESHOLD), Some(Point::new(0.0, 50.0)));
+    }
+
+    #[test]
+    fn docks_beside_other_windows() {
+        let settings_window = Rect::new(1000.0, 200.0, 500.0, 600.0);
+        // Dropped just left of the settings window, tops nearly aligned
+        let snapped = snap_position(AREA, card(590.0, 210.0), &[], &[settings_window], THRESHOLD);
+        assert_eq!(snapped, Some(Point::new(600.0, 200.0)));
+    }
+
+    #[test]
+    fn ignores_windows_it_does_not_line_up_with() {
+        let far_below = Rect::new(1000.0, 900.0, 500.0, 100.0);
+        assert_eq!(snap_position(AREA, card(590.0, 200.0), &[], &[far_below], THRESHOLD), None);
+    }
+}
@@ -0,0 +1,272 @@
+//! Named timers shared by the whole backend. Arming a name that is already
+//! armed replaces its timer, so a trigger that fires again extends the wait
+//! instead of stacking sleeping threads, and any timer can be cancelled by
+//! name from anywhere.
+//!
+//! All timers run on one scheduler thread. Actions should be quick and hand
+//! anything slow to a thread of their own.
+
+use std::collections::HashMap;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, Condvar, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+type Action = Box<dyn FnMut() + Send>;
+
+/// The timer service, managed as Tauri state.
+pub struct Timers {
+    shared: Arc<Shared>,
+}
+
+struct Shared {
+    schedule: Mutex<Schedule>,
+    wake: Condvar,
+}
+
+struct Timer {
+    due: Instant,
+    period: Option<Duration>,
+    /// Tells a re-armed timer apart from the one it replaced
+    generation: u64,
+    /// Taken out while the action runs
+    action: Option<Action>,
+}
+
+#[derive(Default)]
+struct Schedule {
+    timers: HashMap<String, Timer>,
+    generation: u64,
+    stopped: bool,
+}
+
+impl Schedule {
+    fn arm(&mut self, name: &str, due: Instant, period: Option<Duration>, action: Action) {
+        self.generation += 1;
+        let timer = Timer {
+            due,
+            period,
+            generation: self.generation,
+            action: Some(action),
+        };
+        self.timers.insert(name.to_string(), timer);
+    }
+
+    /// When the next timer that is not already running is due.
+    fn next_due(&self) -> Option<Instant> {
+        self.timers.values().filter(|timer| timer.action.is_some()).map(|timer| timer.due).min()
+    }
+
+    /// Takes the actions that are due at `now`. One-shot timers are removed;
+    /// repeating ones move on by their period, without catching up on ticks
+    /// that were missed while the machine slept.
+    fn take_due(&mut self, now: Instant) -> Vec<(String, u64, Action)> {
+        let due: Vec<String> = self
+            .timers
+            .iter()
+            .filter(|(_, timer)| timer.action.is_some() && timer.due <= now)
+            .map(|(name, _)| name.clone())
+            .collect();
+
+        due.into_iter()
+            .filter_map(|name| {
+                let timer = self.timers.get_mut(&name)?;
+                let generation = timer.generation;
+                let action = match timer.period {
+                    Some(period) => {
+                        timer.due = (timer.due + period).max(now);
+                        timer.action.take()?
+                    }
+                    None => self.timers.remove(&name)?.action?,
+                };
+                Some((name, generation, action))
+            })
+            .collect()
+    }
+
+    /// Hands a repeating timer its action back after it ran, unless the
+    /// timer was cancelled or re-armed in the meantime.
+    fn restore(&mut self, name: &str, generation: u64, action: Action) {
+        if let Some(timer) = self.timers.get_mut(name) {
+            if timer.generation == generation && timer.period.is_some() {
+                timer.action = Some(action);
+            }
+        }
+    }
+}
+
+impl Timers {
+    /// Starts the scheduler thread.
+    pub fn start() -> Timers {
+        let shared = Arc::new(Shared {
+            schedule: Mutex::new(Schedule::default()),
+            wake: Condvar::new(),
+        });
+        let worker = Arc::clone(&shared);
+        std::thread::spawn(move || run(&worker));
+        Timers { shared }
+    }
+
+    /// Runs `action` once after `delay`, replacing any timer named `name`.
+    pub fn once(&self, name: &str, delay: Duration, action: impl FnOnce() + Send + 'static) {
+        let mut action = Some(action);
+        self.arm(name, delay, None, Box::new(move || {
+            if let Some(action) = action.take() {
+                action();
+            }
+        }));
+    }
+
+    /// Runs `action` every `period`, starting one period from now and
+    /// replacing any timer named `name`.
+    pub fn repeat(&self, name: &str, period: Duration, action: impl FnMut() + Send + 'static) {
+        self.arm(name, period, Some(period), Box::new(action));
+    }
+
+    /// Stops the timer named `name`. Returns whether one was armed.
+    pub fn cancel(&self, name: &str) -> bool {
+        let removed = crate::lock(&self.shared.schedule).timers.remove(name).is_some();
+        self.shared.wake.notify_one();
+        removed
+    }
+
+    /// The period of a repeating timer, or `None` for one-shot and unarmed
+    /// timers.
+    pub fn period(&self, name: &str) -> Option<Duration> {
+        crate::lock(&self.shared.schedule).timers.get(name).and_then(|timer| timer.period)
+    }
+
+    fn arm(&self, name: &str, delay: Duration, period: Option<Duration>, action: Action) {
+        crate::lock(&self.shared.schedule).arm(name, Instant::now() + delay, period, action);
+        self.shared.wake.notify_one();
+    }
+}
+
+impl Drop for Timers {
+    fn drop(&mut self) {
+        crate::lock(&self.shared.schedule).stopped = true;
+        self.shared.wake.notify_one();
+    }
+}
+
+fn run(shared: &Shared) {
+    let mut schedule = crate::lock(&shared.schedule);
+    while !schedule.stopped {
+        let now = Instant::now();
+        let due = schedule.take_due(now);
+        if due.is_empty() {
+            schedule = match schedule.next_due() {
+                Some(at) => shared.wake.wait_timeout(schedule, at - now).unwrap_or_else(PoisonError::into_inner).0,
+                None => shared.wake.wait(schedule).unwrap_or_else(PoisonError::into_inner),
+            };
+            continue;
+        }
+
+        drop(schedule);
+        for (name, generation, mut action) in due {
+            if panic::catch_unwind(AssertUnwindSafe(&mut action)).is_err() {
+                println!("⚠️ Timer '{}' panicked", name);
+            }
+            crate::lock(&shared.schedule).restore(&name, generation, action);
+        }
+        schedule = crate::lock(&shared.schedule);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    const SHORT: Duration = Duration::from_millis(20);
+    const WAIT: Duration = Duration::from_secs(2);
+
+    fn noop() -> Action {
+        Box::new(|| {})
+    }
+
+    #[test]
+    fn takes_only_due_timers() {
+        let start = Instant::now();
+        let mut schedule = Schedule::default();
+        schedule.arm("soon", start + SHORT, None, noop());
+        schedule.arm("later", start + WAIT, None, noop());
+
+        assert!(schedule.take_due(start).is_empty());
+        assert_eq!(schedule.next_due(), Some(start + SHORT));
+
+        let due = schedule.take_due(start + SHORT);
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].0, "soon");
+        assert!(!schedule.timers.contains_key("soon"));
+        assert_eq!(schedule.next_due(), Some(start + WAIT));
+    }
+
+    #[test]
+    fn repeating_timers_skip_missed_ticks() {
+        let start = Instant::now();
+        let mut schedule = Schedule::default();
+        schedule.arm("tick", start + SHORT, Some(SHORT), noop());
+
+        let late = start + SHORT * 10;
+        let (name, generation, action) = schedule.take_due(late).pop().unwrap();
+        assert_eq!(schedule.next_due(), None, "a running timer is not scheduled");
+
+        schedule.restore(&name, generation, action);
+        assert_eq!(schedule.next_due(), Some(late));
+    }
+
+    #[test]
+    fn rearming_while_running_drops_the_old_action() {
+        let start = Instant::now();
+        let mut schedule = Schedule::default();
+        schedule.arm("tick", start, Some(SHORT), noop());
+        let (name, generation, action) = schedule.take_due(start).pop().unwrap();
+
+        schedule.arm("tick", start + WAIT, Some(WAIT), noop());
+        schedule.restore(&name, generation, action);
+        assert_eq!(schedule.timers["tick"].period, Some(WAIT));
+        assert_eq!(schedule.next_due(), Some(start + WAIT));
+    }
+
+    #[test]
+    fn rearming_replaces_instead_of_stacking() {
+        let timers = Timers::start();
+        let (sender, receiver) = mpsc::channel();
+        for press in 0..5 {
+            let sender = sender.clone();
+            timers.once("reveal", SHORT * 2, move || sender.send(press).unwrap());
+        }
+
+        assert_eq!(receiver.recv_timeout(WAIT), Ok(4));
+        assert!(receiver.recv_timeout(SHORT * 4).is_err());
+        assert!(!timers.cancel("reveal"), "a fired one-shot timer is disarmed");
+    }
+
+    #[test]
+    fn cancelled_timers_do_not_fire() {
+        let timers = Timers::start();
+        let (sender, receiver) = mpsc::channel();
+        timers.once("reveal", SHORT, move || sender.send(()).unwrap());
+
+        assert!(timers.cancel("reveal"));
+        assert!(!timers.cancel("reveal"));
+        assert!(receiver.recv_timeout(SHORT * 4).is_err());
+    }
+
+    #[test]
+    fn repeating_timers_keep_firing_after_a_panic() {
+        let timers = Timers::start();
+        let (sender, receiver) = mpsc::channel();
+        let mut count = 0;
+        timers.repeat("tick", SHORT, move || {
+            count += 1;
+            sender.send(count).unwrap();
+            assert!(count != 1, "first tick fails");
+        });
+
+        assert_eq!(receiver.recv_timeout(WAIT), Ok(1));
+        assert_eq!(receiver.recv_timeout(WAIT), Ok(2));
+        assert_eq!(timers.period("tick"), Some(SHORT));
+        assert!(timers.cancel("tick"));
+    }
+}
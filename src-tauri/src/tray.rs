@@ -0,0 +1,339 @@
+//! The system tray menu. Its layout (profiles, favorite topics) follows the
+//! settings and is rebuilt only when that changes; the items that follow
+//! the runtime state (current card, pause, click-through, window) are
+//! updated in place, so an open menu is not torn down every card. The
+//! tooltip and icon variant follow the runtime state as well; see `status`.
+
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tauri::{CustomMenuItem, Icon, Manager, SystemTrayMenu, SystemTrayMenuItem, SystemTraySubmenu};
+
+use crate::app_state::{AppState, StateStore, WindowMode};
+use crate::settings::{self, ProfilesInfo, Settings, SettingsStore};
+
+mod status;
+
+use status::TrayStatus;
+
+const PROFILE_MENU_PREFIX: &str = "profile:";
+const TOPIC_MENU_PREFIX: &str = "topic:";
+
+const CURRENT_CARD_ITEM: &str = "current-card";
+const PAUSE_ITEM: &str = "pause";
+const CLICK_THROUGH_ITEM: &str = "click-through";
+const TOGGLE_WINDOW_ITEM: &str = "toggle-window";
+
+/// How the main window names a topic. Only the frontend knows the topic
+/// catalogue, so it reports the entries the tray may show.
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+pub struct TopicLabel {
+    pub code: String,
+    pub name: String,
+    pub icon: String,
+}
+
+/// Everything the menu layout depends on; the menu is rebuilt when it changes.
+#[derive(Clone, Debug, PartialEq, Default)]
+struct Layout {
+    profiles: ProfilesInfo,
+    favorite_topics: Vec<String>,
+    selected_topic: String,
+    labels: HashMap<String, TopicLabel>,
+}
+
+/// The configured tray icon as RGBA, which the variants are drawn from.
+struct BaseIcon {
+    rgba: Vec<u8>,
+    width: u32,
+    height: u32,
+}
+
+/// The tray bookkeeping, managed as Tauri state.
+pub struct Tray {
+    base_icon: Option<BaseIcon>,
+    layout: Mutex<Option<Layout>>,
+    labels: Mutex<HashMap<String, TopicLabel>>,
+    /// The icon variant and tooltip last shown
+    shown: Mutex<(Option<TrayStatus>, String)>,
+}
+
+impl Tray {
+    /// `icon` is the tray icon from the app config. Without one in RGBA
+    /// form the icon never changes, but the tooltip still does.
+    pub fn new(icon: Option<Icon>) -> Tray {
+        let base_icon = match icon {
+            Some(Icon::Rgba { rgba, width, height }) => Some(BaseIcon { rgba, width, height }),
+            _ => None,
+        };
+        Tray {
+            base_icon,
+            layout: Mutex::new(None),
+            labels: Mutex::new(HashMap::new()),
+            shown: Mutex::new((None, String::new())),
+        }
+    }
+}
+
+fn card_title(state: &AppState) -> String {
+    match &state.current_card {
+        Some(card) => match &card.translation {
+            Some(translation) if !translation.is_empty() => format!("{} — {}", card.text, translation),
+            _ => card.text.clone(),
+        },
+        None => "No card yet".to_string(),
+    }
+}
+
+fn pause_title(state: &AppState) -> &'static str {
+    if state.paused {
+        "Resume Rotation"
+    } else {
+        "Pause Rotation"
+    }
+}
+
+fn window_title(state: &AppState) -> &'static str {
+    match state.window_mode {
+        WindowMode::Visible => "Hide Card",
+        WindowMode::Hidden => "Show Card",
+    }
+}
+
+fn topic_title(code: &str, labels: &HashMap<String, TopicLabel>) -> String {
+    match labels.get(code) {
+        Some(label) => format!("{} {}", label.icon, label.name),
+        None => code.to_string(),
+    }
+}
+
+fn build_menu(layout: &Layout, state: &AppState) -> SystemTrayMenu {
+    let mut profiles_menu = SystemTrayMenu::new();
+    for name in &layout.profiles.names {
+        let mut item = CustomMenuItem::new(format!("{}{}", PROFILE_MENU_PREFIX, name), name);
+        if *name == layout.profiles.active {
+            item = item.selected();
+        }
+        profiles_menu = profiles_menu.add_item(item);
+    }
+
+    let mut topics_menu = SystemTrayMenu::new();
+    for code in &layout.favorite_topics {
+        let mut item = CustomMenuItem::new(format!("{}{}", TOPIC_MENU_PREFIX, code), topic_title(code, &layout.labels));
+        if *code == layout.selected_topic {
+            item = item.selected();
+        }
+        topics_menu = topics_menu.add_item(item);
+    }
+    if layout.favorite_topics.is_empty() {
+        topics_menu = topics_menu.add_item(CustomMenuItem::new("no-topics", "No favorite topics").disabled());
+    }
+
+    let mut click_through = CustomMenuItem::new(CLICK_THROUGH_ITEM, "Click-Through");
+    if state.click_through {
+        click_through = click_through.selected();
+    }
+
+    SystemTrayMenu::new()
+        .add_item(CustomMenuItem::new(CURRENT_CARD_ITEM, card_title(state)).disabled())
+        .add_native_item(SystemTrayMenuItem::Separator)
+        .add_submenu(SystemTraySubmenu::new("Topics", topics_menu))
+        .add_item(CustomMenuItem::new(PAUSE_ITEM, pause_title(state)))
+        .add_item(click_through)
+        .add_item(CustomMenuItem::new(TOGGLE_WINDOW_ITEM, window_title(state)))
+        .add_native_item(SystemTrayMenuItem::Separator)
+        .add_item(CustomMenuItem::new("preferences", "Preferences"))
+        .add_submenu(SystemTraySubmenu::new("Profiles", profiles_menu))
+        .add_native_item(SystemTrayMenuItem::Separator)
+        .add_item(CustomMenuItem::new("quit", "Quit"))
+}
+
+/// The menu to start with, before the settings are loaded.
+pub fn initial_menu() -> SystemTrayMenu {
+    let layout = Layout {
+        profiles: settings::Profiles::default().info(),
+        ..Layout::default()
+    };
+    build_menu(&layout, &AppState::default())
+}
+
+/// Rebuilds the menu if the profiles, favorite topics or topic labels
+/// changed since it was last built.
+pub fn refresh(app_handle: &tauri::AppHandle) {
+    let store = app_handle.state::<SettingsStore>();
+    let settings: Settings = store.get();
+    let tray = app_handle.state::<Tray>();
+    let layout = Layout {
+        profiles: store.profiles(),
+        favorite_topics: settings.favorite_topics,
+        selected_topic: settings.selected_topic_code,
+        labels: crate::lock(&tray.labels).clone(),
+    };
+
+    let mut current = crate::lock(&tray.layout);
+    if current.as_ref() == Some(&layout) {
+        return;
+    }
+    let state = app_handle.state::<StateStore>().get();
+    match app_handle.tray_handle().set_menu(build_menu(&layout, &state)) {
+        Ok(()) => *current = Some(layout),
+        Err(e) => println!("⚠️ Failed to update tray menu: {}", e),
+    }
+}
+
+/// Brings the state-driven items, the tooltip and the icon in line with
+/// `state`. Called by the state store on every change.
+pub fn update(app_handle: &tauri::AppHandle, state: &AppState) {
+    show_status(app_handle, state);
+    let tray = app_handle.tray_handle();
+    let updates = [
+        tray.try_get_item(CURRENT_CARD_ITEM).map(|item| item.set_title(card_title(state))),
+        tray.try_get_item(PAUSE_ITEM).map(|item| item.set_title(pause_title(state))),
+        tray.try_get_item(CLICK_THROUGH_ITEM).map(|item| item.set_selected(state.click_through)),
+        tray.try_get_item(TOGGLE_WINDOW_ITEM).map(|item| item.set_title(window_title(state))),
+    ];
+    for result in updates.into_iter().flatten() {
+        if let Err(e) = result {
+            println!("⚠️ Failed to update tray item: {}", e);
+        }
+    }
+}
+
+/// Updates the tooltip and icon variant, touching each only when it changed.
+fn show_status(app_handle: &tauri::AppHandle, state: &AppState) {
+    let handle = app_handle.tray_handle();
+    let tray = app_handle.state::<Tray>();
+    let mut shown = crate::lock(&tray.shown);
+
+    let tooltip = status::tooltip(state);
+    if shown.1 != tooltip {
+        if let Err(e) = handle.set_tooltip(&tooltip) {
+            println!("⚠️ Failed to update tray tooltip: {}", e);
+        }
+        shown.1 = tooltip;
+    }
+
+    let status = TrayStatus::of(state);
+    if shown.0 == Some(status) {
+        return;
+    }
+    shown.0 = Some(status);
+    let Some(base) = &tray.base_icon else {
+        return;
+    };
+    let icon = Icon::Rgba {
+        rgba: status::icon_variant(&base.rgba, base.width, base.height, status),
+        width: base.width,
+        height: base.height,
+    };
+    if let Err(e) = handle.set_icon(icon) {
+        println!("⚠️ Failed to update tray icon: {}", e);
+    }
+    // A template icon is drawn in the menu bar color, which would hide the badges
+    #[cfg(target_os = "macos")]
+    {
+        let _ = handle.set_icon_as_template(matches!(status, TrayStatus::Normal | TrayStatus::Paused));
+    }
+}
+
+/// Handles a click on a menu item.
+pub fn on_menu_click(app_handle: &tauri::AppHandle, id: &str) {
+    match id {
+        "quit" => app_handle.exit(0),
+        "preferences" => {
+            if let Some(window) = app_handle.get_window("settings") {
+                let _ = window.show();
+                let _ = window.set_focus();
+                let _ = window.center();
+            }
+        }
+        PAUSE_ITEM => crate::app_state::toggle_paused(app_handle),
+        CLICK_THROUGH_ITEM => {
+            crate::toggle_click_through(app_handle.clone(), app_handle.state());
+        }
+        TOGGLE_WINDOW_ITEM => crate::toggle_main_window(app_handle),
+        other => {
+            if let Some(name) = other.strip_prefix(PROFILE_MENU_PREFIX) {
+                let store = app_handle.state::<SettingsStore>();
+                if let Err(e) = store.modify_profiles(app_handle, |profiles| profiles.switch(name)) {
+                    println!("⚠️ Failed to switch profile from tray: {}", e);
+                }
+            } else if let Some(code) = other.strip_prefix(TOPIC_MENU_PREFIX) {
+                select_topic(app_handle, code);
+            }
+        }
+    }
+}
+
+fn select_topic(app_handle: &tauri::AppHandle, code: &str) {
+    let label = crate::lock(&app_handle.state::<Tray>().labels).get(code).cloned();
+    let patch = match label {
+        Some(label) => json!({
+            "selectedTopic": label.name,
+            "selectedTopicCode": label.code,
+            "selectedTopicIcon": label.icon,
+        }),
+        None => json!({ "selectedTopicCode": code }),
+    };
+    if let Err(e) = app_handle.state::<SettingsStore>().update(app_handle, &patch) {
+        println!("⚠️ Failed to switch topic from tray: {}", e);
+    }
+}
+
+/// Tells the tray how to name the topics the main window offers.
+#[tauri::command]
+pub fn set_topic_labels(app_handle: tauri::AppHandle, tray: tauri::State<Tray>, labels: Vec<TopicLabel>) {
+    let labels = labels.into_iter().map(|label| (label.code.clone(), label)).collect();
+    *crate::lock(&tray.labels) = labels;
+    refresh(&app_handle);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app_state::CurrentCard;
+
+    fn card(text: &str, translation: Option<&str>) -> AppState {
+        AppState {
+            current_card: Some(CurrentCard {
+                index: 0,
+                text: text.to_string(),
+                translation: translation.map(str::to_string),
+            }),
+            ..AppState::default()
+        }
+    }
+
+    #[test]
+    fn card_title_includes_the_translation_when_known() {
+        assert_eq!(card_title(&card("Chien", Some("Dog"))), "Chien — Dog");
+        assert_eq!(card_title(&card("Chien", Some(""))), "Chien");
+        assert_eq!(card_title(&card("Chien", None)), "Chien");
+        assert_eq!(card_title(&AppState::default()), "No card yet");
+    }
+
+    #[test]
+    fn titles_describe_the_next_step() {
+        let mut state = AppState::default();
+        assert_eq!((pause_title(&state), window_title(&state)), ("Pause Rotation", "Hide Card"));
+        state.paused = true;
+        state.window_mode = WindowMode::Hidden;
+        assert_eq!((pause_title(&state), window_title(&state)), ("Resume Rotation", "Show Card"));
+    }
+
+    #[test]
+    fn unlabelled_topics_fall_back_to_their_code() {
+        let mut labels = HashMap::new();
+        labels.insert(
+            "FR".to_string(),
+            TopicLabel {
+                code: "FR".to_string(),
+                name: "French".to_string(),
+                icon: "🇫🇷".to_string(),
+            },
+        );
+        assert_eq!(topic_title("FR", &labels), "🇫🇷 French");
+        assert_eq!(topic_title("DE", &labels), "DE");
+    }
+}
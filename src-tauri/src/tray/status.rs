@@ -0,0 +1,149 @@
+//! What the tray icon and tooltip say about the app: reviews due, the grading
+//! streak and whether Ollama can be reached. The icon variants are drawn
+//! from the configured tray icon at runtime, so no extra assets are needed.
+
+use crate::app_state::{AppState, ProviderStatus};
+
+/// Which icon variant the tray shows.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TrayStatus {
+    Normal,
+    Offline,
+    Generating,
+    Paused,
+}
+
+impl TrayStatus {
+    /// The most pressing condition wins; a dead provider matters more than
+    /// a deliberate pause.
+    pub fn of(state: &AppState) -> TrayStatus {
+        match state.provider_status {
+            ProviderStatus::Offline => TrayStatus::Offline,
+            ProviderStatus::Generating => TrayStatus::Generating,
+            _ if state.paused => TrayStatus::Paused,
+            _ => TrayStatus::Normal,
+        }
+    }
+
+    /// Corner badge color, as RGB.
+    fn badge(self) -> Option<[u8; 3]> {
+        match self {
+            TrayStatus::Offline => Some([0xd9, 0x53, 0x4f]),
+            TrayStatus::Generating => Some([0x33, 0x7a, 0xb7]),
+            TrayStatus::Normal | TrayStatus::Paused => None,
+        }
+    }
+}
+
+pub fn tooltip(state: &AppState) -> String {
+    let provider = match state.provider_status {
+        ProviderStatus::Unknown => "Ollama: checking",
+        ProviderStatus::Online => "Ollama online",
+        ProviderStatus::Offline => "Ollama offline",
+        ProviderStatus::Generating => "Generating cards…",
+    };
+    let mut parts = vec![
+        format!("{} due", state.session.due()),
+        format!("streak {}", state.session.streak),
+        provider.to_string(),
+    ];
+    if state.paused {
+        parts.push("paused".to_string());
+    }
+    format!("Floatlearn — {}", parts.join(" · "))
+}
+
+/// Draws the variant of an RGBA icon for `status`: offline is greyed out,
+/// paused is faded, and offline and generating get a colored corner badge.
+pub fn icon_variant(rgba: &[u8], width: u32, height: u32, status: TrayStatus) -> Vec<u8> {
+    let mut pixels = rgba.to_vec();
+    for pixel in pixels.chunks_exact_mut(4) {
+        match status {
+            TrayStatus::Offline => {
+                let luma = (0.299 * pixel[0] as f32 + 0.587 * pixel[1] as f32 + 0.114 * pixel[2] as f32) as u8;
+                pixel[..3].fill(luma);
+            }
+            TrayStatus::Paused => pixel[3] /= 2,
+            TrayStatus::Normal | TrayStatus::Generating => {}
+        }
+    }
+
+    if let Some(color) = status.badge() {
+        let radius = (width.min(height) as f32 / 5.0).max(2.0);
+        let (cx, cy) = (width as f32 - radius - 1.0, height as f32 - radius - 1.0);
+        for y in 0..height {
+            for x in 0..width {
+                let (dx, dy) = (x as f32 + 0.5 - cx, y as f32 + 0.5 - cy);
+                if dx * dx + dy * dy <= radius * radius {
+                    let i = ((y * width + x) * 4) as usize;
+                    pixels[i..i + 3].copy_from_slice(&color);
+                    pixels[i + 3] = 0xff;
+                }
+            }
+        }
+    }
+    pixels
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIZE: u32 = 16;
+
+    fn solid(rgba: [u8; 4]) -> Vec<u8> {
+        rgba.iter().copied().cycle().take((SIZE * SIZE * 4) as usize).collect()
+    }
+
+    fn pixel(pixels: &[u8], x: u32, y: u32) -> &[u8] {
+        let i = ((y * SIZE + x) * 4) as usize;
+        &pixels[i..i + 4]
+    }
+
+    #[test]
+    fn offline_outranks_pause() {
+        let mut state = AppState {
+            paused: true,
+            ..AppState::default()
+        };
+        assert_eq!(TrayStatus::of(&state), TrayStatus::Paused);
+        state.provider_status = ProviderStatus::Offline;
+        assert_eq!(TrayStatus::of(&state), TrayStatus::Offline);
+        state.provider_status = ProviderStatus::Generating;
+        assert_eq!(TrayStatus::of(&state), TrayStatus::Generating);
+    }
+
+    #[test]
+    fn tooltip_lists_due_streak_and_provider() {
+        let mut state = AppState::default();
+        state.session.deck = vec!["chien".to_string(), "chat".to_string()];
+        state.session.streak = 4;
+        state.provider_status = ProviderStatus::Online;
+        assert_eq!(tooltip(&state), "Floatlearn — 2 due · streak 4 · Ollama online");
+        state.paused = true;
+        assert!(tooltip(&state).ends_with(" · paused"));
+    }
+
+    #[test]
+    fn normal_icon_is_unchanged() {
+        let icon = solid([10, 200, 30, 255]);
+        assert_eq!(icon_variant(&icon, SIZE, SIZE, TrayStatus::Normal), icon);
+    }
+
+    #[test]
+    fn variants_grey_fade_and_badge() {
+        let icon = solid([200, 40, 40, 255]);
+
+        let offline = icon_variant(&icon, SIZE, SIZE, TrayStatus::Offline);
+        let top_left = pixel(&offline, 0, 0);
+        assert!(top_left[0] == top_left[1] && top_left[1] == top_left[2], "greyed out");
+        assert_eq!(pixel(&offline, SIZE - 3, SIZE - 3), &[0xd9, 0x53, 0x4f, 0xff]);
+
+        let paused = icon_variant(&icon, SIZE, SIZE, TrayStatus::Paused);
+        assert_eq!(pixel(&paused, SIZE - 1, SIZE - 1), &[200, 40, 40, 127]);
+
+        let generating = icon_variant(&icon, SIZE, SIZE, TrayStatus::Generating);
+        assert_eq!(pixel(&generating, 0, 0), &[200, 40, 40, 255]);
+        assert_eq!(pixel(&generating, SIZE - 3, SIZE - 3)[..3], [0x33, 0x7a, 0xb7]);
+    }
+}
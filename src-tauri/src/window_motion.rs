@@ -0,0 +1,204 @@
+//! Animated window movement. Moves follow an eased path on a background
+//! thread; starting a new move, or a drag, cancels the one in flight so the
+//! window never gets pulled in two directions.
+
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::PhysicalPosition;
+
+use crate::settings::Settings;
+
+/// Time between animation frames, roughly 60 fps.
+const FRAME_INTERVAL: Duration = Duration::from_millis(16);
+
+/// Longest allowed animation, so a bad setting cannot leave the card crawling.
+pub const MAX_DURATION_MS: u32 = 3000;
+
+/// The shape of a move over time.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Easing {
+    Linear,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+}
+
+impl Easing {
+    /// Progress along the path at time `t`, both in `0.0..=1.0`. `t` is
+    /// clamped, so the curve always starts at 0 and ends at 1.
+    pub fn apply(self, t: f64) -> f64 {
+        let t = if t.is_nan() { 1.0 } else { t.clamp(0.0, 1.0) };
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t * t,
+            Easing::EaseOut => 1.0 - (1.0 - t).powi(3),
+            Easing::EaseInOut => {
+                if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+                }
+            }
+        }
+    }
+}
+
+/// Point `progress` of the way from `start` to `end`, rounded to whole pixels.
+pub fn interpolate(start: PhysicalPosition<i32>, end: PhysicalPosition<i32>, progress: f64) -> PhysicalPosition<i32> {
+    let lerp = |a: i32, b: i32| (f64::from(a) + f64::from(b - a) * progress).round() as i32;
+    PhysicalPosition::new(lerp(start.x, end.x), lerp(start.y, end.y))
+}
+
+/// How a window should get to its new position.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Motion {
+    pub duration: Duration,
+    pub easing: Easing,
+}
+
+impl Motion {
+    pub const INSTANT: Motion = Motion {
+        duration: Duration::ZERO,
+        easing: Easing::Linear,
+    };
+
+    /// The movement animation configured in `settings`.
+    pub fn from_settings(settings: &Settings) -> Motion {
+        if !settings.smooth_movement {
+            return Motion::INSTANT;
+        }
+        Motion {
+            duration: Duration::from_millis(u64::from(settings.movement_duration_ms)),
+            easing: settings.movement_easing,
+        }
+    }
+}
+
+/// Tracks the move in flight, managed as Tauri state. Every move takes a new
+/// generation number; an animation stops as soon as it is no longer the
+/// latest one.
+#[derive(Default)]
+pub struct WindowMotion {
+    generation: Arc<AtomicU64>,
+}
+
+impl WindowMotion {
+    /// Stops any animation in flight, leaving the window where it is.
+    pub fn cancel(&self) {
+        self.generation.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// Moves `window` to `target`, animated unless `motion` is instant. The
+    /// animation runs on its own thread and this returns straight away.
+    pub fn move_to(&self, window: &tauri::Window, target: PhysicalPosition<i32>, motion: Motion) -> tauri::Result<()> {
+        let generation = self.generation.fetch_add(1, Ordering::SeqCst) + 1;
+        if motion.duration.is_zero() {
+            return window.set_position(target);
+        }
+
+        let start = window.outer_position()?;
+        if start == target {
+            return Ok(());
+        }
+
+        let latest = Arc::clone(&self.generation);
+        let window = window.clone();
+        std::thread::spawn(move || {
+            let began = Instant::now();
+            loop {
+                if latest.load(Ordering::SeqCst) != generation {
+                    return;
+                }
+                let t = began.elapsed().as_secs_f64() / motion.duration.as_secs_f64();
+                if t >= 1.0 {
+                    break;
+                }
+                if window.set_position(interpolate(start, target, motion.easing.apply(t))).is_err() {
+                    return;
+                }
+                std::thread::sleep(FRAME_INTERVAL);
+            }
+            if let Err(e) = window.set_position(target) {
+                println!("⚠️ Failed to finish window move: {}", e);
+            }
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    const ALL: [Easing; 4] = [Easing::Linear, Easing::EaseIn, Easing::EaseOut, Easing::EaseInOut];
+
+    #[test]
+    fn curves_start_at_zero_and_end_at_one() {
+        for easing in ALL {
+            assert_eq!(easing.apply(0.0), 0.0, "{:?}", easing);
+            assert_eq!(easing.apply(1.0), 1.0, "{:?}", easing);
+        }
+    }
+
+    #[test]
+    fn out_of_range_time_is_clamped() {
+        for easing in ALL {
+            assert_eq!(easing.apply(-0.5), 0.0);
+            assert_eq!(easing.apply(7.0), 1.0);
+            assert_eq!(easing.apply(f64::NAN), 1.0);
+        }
+    }
+
+    #[test]
+    fn curves_have_the_expected_shape() {
+        assert_eq!(Easing::Linear.apply(0.25), 0.25);
+        // Ease-in starts slowly, ease-out finishes slowly
+        assert!(Easing::EaseIn.apply(0.25) < 0.25);
+        assert!(Easing::EaseOut.apply(0.25) > 0.25);
+        assert_eq!(Easing::EaseInOut.apply(0.5), 0.5);
+    }
+
+    #[test]
+    fn interpolates_in_both_directions() {
+        let start = PhysicalPosition::new(100, 800);
+        let end = PhysicalPosition::new(300, 200);
+        assert_eq!(interpolate(start, end, 0.0), start);
+        assert_eq!(interpolate(start, end, 0.5), PhysicalPosition::new(200, 500));
+        assert_eq!(interpolate(start, end, 1.0), end);
+    }
+
+    #[test]
+    fn disabled_smooth_movement_is_instant() {
+        let settings = Settings {
+            smooth_movement: false,
+            ..Settings::default()
+        };
+        assert_eq!(Motion::from_settings(&settings), Motion::INSTANT);
+    }
+
+    proptest! {
+        #[test]
+        fn curves_are_monotonic(a in 0.0..=1.0f64, b in 0.0..=1.0f64) {
+            let (early, late) = if a <= b { (a, b) } else { (b, a) };
+            for easing in ALL {
+                prop_assert!(easing.apply(early) <= easing.apply(late) + 1e-12);
+            }
+        }
+
+        #[test]
+        fn ease_in_out_is_symmetric(t in 0.0..=1.0f64) {
+            let mirrored = 1.0 - Easing::EaseInOut.apply(1.0 - t);
+            prop_assert!((Easing::EaseInOut.apply(t) - mirrored).abs() < 1e-9);
+        }
+
+        #[test]
+        fn interpolation_stays_between_the_endpoints(x0 in -5000..5000i32, x1 in -5000..5000i32, t in 0.0..=1.0f64) {
+            let x = interpolate(PhysicalPosition::new(x0, 0), PhysicalPosition::new(x1, 0), t).x;
+            prop_assert!(x >= x0.min(x1) && x <= x0.max(x1));
+        }
+    }
+}
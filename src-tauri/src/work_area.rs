@@ -0,0 +1,218 @@
+//! The usable part of each monitor, excluding panels, docks and taskbars.
+//!
+//! Tauri only reports full monitor rectangles. On Linux/X11 the window
+//! manager publishes the reserved space through `_NET_WORKAREA` and the
+//! `_NET_WM_STRUT(_PARTIAL)` properties of panel windows, which are read
+//! here. Elsewhere the work area is the whole monitor for now.
+
+use crate::layout::Rect;
+
+#[cfg(target_os = "linux")]
+pub(crate) mod x11;
+
+/// Space reserved along one screen edge by a panel, in root window pixels,
+/// as described by `_NET_WM_STRUT_PARTIAL`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Strut {
+    pub left: f64,
+    pub right: f64,
+    pub top: f64,
+    pub bottom: f64,
+    pub left_start_y: f64,
+    pub left_end_y: f64,
+    pub right_start_y: f64,
+    pub right_end_y: f64,
+    pub top_start_x: f64,
+    pub top_end_x: f64,
+    pub bottom_start_x: f64,
+    pub bottom_end_x: f64,
+}
+
+impl Strut {
+    /// Parses the 12 cardinals of `_NET_WM_STRUT_PARTIAL`, or the 4 of the
+    /// older `_NET_WM_STRUT`, which reserves the full length of each edge.
+    pub fn from_cardinals(values: &[i64], root_width: f64, root_height: f64) -> Option<Strut> {
+        let v = |i: usize| values[i] as f64;
+        match values.len() {
+            n if n >= 12 => Some(Strut {
+                left: v(0),
+                right: v(1),
+                top: v(2),
+                bottom: v(3),
+                left_start_y: v(4),
+                left_end_y: v(5),
+                right_start_y: v(6),
+                right_end_y: v(7),
+                top_start_x: v(8),
+                top_end_x: v(9),
+                bottom_start_x: v(10),
+                bottom_end_x: v(11),
+            }),
+            n if n >= 4 => Some(Strut {
+                left: v(0),
+                right: v(1),
+                top: v(2),
+                bottom: v(3),
+                left_start_y: 0.0,
+                left_end_y: root_height - 1.0,
+                right_start_y: 0.0,
+                right_end_y: root_height - 1.0,
+                top_start_x: 0.0,
+                top_end_x: root_width - 1.0,
+                bottom_start_x: 0.0,
+                bottom_end_x: root_width - 1.0,
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// What the window manager reports about reserved screen space.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct WorkAreas {
+    /// Width and height of the root window (the whole virtual screen)
+    root: Option<(f64, f64)>,
+    /// `_NET_WORKAREA` for the current desktop
+    net_workarea: Option<Rect>,
+    struts: Vec<Strut>,
+}
+
+impl WorkAreas {
+    /// Reads the current work area hints. Returns empty hints, meaning
+    /// "use the whole monitor", where they are not available.
+    pub fn current() -> WorkAreas {
+        #[cfg(target_os = "linux")]
+        {
+            if let Some(work_areas) = x11::read() {
+                return work_areas;
+            }
+        }
+        WorkAreas::default()
+    }
+
+    /// The usable part of the monitor with physical `bounds`.
+    ///
+    /// `_NET_WORKAREA` is a single rectangle for the whole virtual screen, so
+    /// it is only trusted where it overlaps the monitor; struts then trim the
+    /// edges of the monitor they actually touch, which also handles panels
+    /// on inner edges between monitors.
+    pub fn for_monitor(&self, bounds: Rect) -> Rect {
+        let mut area = self
+            .net_workarea
+            .and_then(|workarea| bounds.intersection(&workarea))
+            .unwrap_or(bounds);
+
+        if let Some((root_width, root_height)) = self.root {
+            for strut in &self.struts {
+                area = apply_strut(area, strut, root_width, root_height);
+            }
+        }
+        area
+    }
+}
+
+/// Shrinks `area` to avoid the screen space reserved by `strut`.
+fn apply_strut(area: Rect, strut: &Strut, root_width: f64, root_height: f64) -> Rect {
+    let mut left = area.x;
+    let mut top = area.y;
+    let mut right = area.right();
+    let mut bottom = area.bottom();
+
+    // Ranges in struts are inclusive pixel coordinates
+    let spans = |start: f64, end: f64, from: f64, to: f64| start < to && end + 1.0 > from;
+
+    if strut.left > 0.0 && strut.left > left && spans(strut.left_start_y, strut.left_end_y, top, bottom) {
+        left = left.max(strut.left);
+    }
+    if strut.right > 0.0 && root_width - strut.right < right && spans(strut.right_start_y, strut.right_end_y, top, bottom) {
+        right = right.min(root_width - strut.right);
+    }
+    if strut.top > 0.0 && strut.top > top && spans(strut.top_start_x, strut.top_end_x, left, right) {
+        top = top.max(strut.top);
+    }
+    if strut.bottom > 0.0 && root_height - strut.bottom < bottom && spans(strut.bottom_start_x, strut.bottom_end_x, left, right) {
+        bottom = bottom.min(root_height - strut.bottom);
+    }
+
+    if right > left && bottom > top {
+        Rect::new(left, top, right - left, bottom - top)
+    } else {
+        // A strut covering the whole monitor is bogus; ignore it
+        area
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LEFT: Rect = Rect { x: 0.0, y: 0.0, width: 1920.0, height: 1080.0 };
+    const RIGHT: Rect = Rect { x: 1920.0, y: 0.0, width: 2560.0, height: 1440.0 };
+    const ROOT: (f64, f64) = (4480.0, 1440.0);
+
+    fn hints(net_workarea: Option<Rect>, struts: Vec<Strut>) -> WorkAreas {
+        WorkAreas {
+            root: Some(ROOT),
+            net_workarea,
+            struts,
+        }
+    }
+
+    fn partial(values: [i64; 12]) -> Strut {
+        Strut::from_cardinals(&values, ROOT.0, ROOT.1).unwrap()
+    }
+
+    #[test]
+    fn no_hints_means_the_whole_monitor() {
+        assert_eq!(WorkAreas::default().for_monitor(LEFT), LEFT);
+    }
+
+    #[test]
+    fn top_bar_only_trims_the_monitor_it_spans() {
+        // A 32px bar across the top of the left monitor only
+        let bar = partial([0, 0, 32, 0, 0, 0, 0, 0, 0, 1919, 0, 0]);
+        let areas = hints(None, vec![bar]);
+        assert_eq!(areas.for_monitor(LEFT), Rect::new(0.0, 32.0, 1920.0, 1048.0));
+        assert_eq!(areas.for_monitor(RIGHT), RIGHT);
+    }
+
+    #[test]
+    fn bottom_dock_is_measured_from_the_root_bottom() {
+        // 64px dock at the bottom of the taller right monitor
+        let dock = partial([0, 0, 0, 64, 0, 0, 0, 0, 0, 0, 1920, 4479]);
+        let areas = hints(None, vec![dock]);
+        assert_eq!(areas.for_monitor(RIGHT), Rect::new(1920.0, 0.0, 2560.0, 1376.0));
+        assert_eq!(areas.for_monitor(LEFT), LEFT);
+    }
+
+    #[test]
+    fn right_panel_trims_the_right_edge() {
+        let panel = partial([0, 48, 0, 0, 0, 0, 0, 1439, 0, 0, 0, 0]);
+        assert_eq!(hints(None, vec![panel]).for_monitor(RIGHT), Rect::new(1920.0, 0.0, 2512.0, 1440.0));
+    }
+
+    #[test]
+    fn legacy_struts_reserve_whole_edges() {
+        let strut = Strut::from_cardinals(&[0, 0, 24, 0], ROOT.0, ROOT.1).unwrap();
+        let areas = hints(None, vec![strut]);
+        assert_eq!(areas.for_monitor(LEFT).y, 24.0);
+        assert_eq!(areas.for_monitor(RIGHT).y, 24.0);
+        assert_eq!(Strut::from_cardinals(&[1, 2], ROOT.0, ROOT.1), None);
+    }
+
+    #[test]
+    fn net_workarea_is_clipped_to_each_monitor() {
+        let areas = hints(Some(Rect::new(0.0, 27.0, 4480.0, 1413.0)), Vec::new());
+        assert_eq!(areas.for_monitor(LEFT), Rect::new(0.0, 27.0, 1920.0, 1053.0));
+        assert_eq!(areas.for_monitor(RIGHT), Rect::new(1920.0, 27.0, 2560.0, 1413.0));
+        // A work area that misses the monitor entirely is ignored
+        let elsewhere = hints(Some(Rect::new(5000.0, 0.0, 100.0, 100.0)), Vec::new());
+        assert_eq!(elsewhere.for_monitor(LEFT), LEFT);
+    }
+
+    #[test]
+    fn bogus_struts_are_ignored() {
+        let everything = partial([0, 0, 2000, 0, 0, 0, 0, 0, 0, 4479, 0, 0]);
+        assert_eq!(hints(None, vec![everything]).for_monitor(LEFT), LEFT);
+    }
+}
@@ -0,0 +1,162 @@
+//! Reads EWMH work area hints from the X server. libX11 is loaded at
+//! runtime, so Wayland-only sessions and missing libraries simply yield no
+//! hints instead of failing to start.
+
+use std::ffi::CString;
+use std::os::raw::{c_int, c_long, c_uchar, c_ulong};
+use std::ptr;
+use std::sync::atomic::{AtomicBool, AtomicPtr, AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use x11_dl::xlib::{self, Display, Xlib};
+
+use super::{Strut, WorkAreas};
+use crate::layout::Rect;
+
+/// Upper bound on the number of 32-bit items read from one property.
+const MAX_ITEMS: c_long = 4096;
+
+/// Reads `_NET_WORKAREA` and the struts of all managed windows, or `None`
+/// when there is no X display.
+pub fn read() -> Option<WorkAreas> {
+    let xlib = Xlib::open().ok()?;
+
+    // SAFETY: the display is checked for null, only used while open, and
+    // closed exactly once below.
+    unsafe {
+        let display = (xlib.XOpenDisplay)(ptr::null());
+        if display.is_null() {
+            return None;
+        }
+
+        let work_areas = read_from(&xlib, display);
+        (xlib.XCloseDisplay)(display);
+        Some(work_areas)
+    }
+}
+
+unsafe fn read_from(xlib: &Xlib, display: *mut Display) -> WorkAreas {
+    let root = (xlib.XDefaultRootWindow)(display);
+    let screen = (xlib.XDefaultScreen)(display);
+    let root_width = (xlib.XDisplayWidth)(display, screen) as f64;
+    let root_height = (xlib.XDisplayHeight)(display, screen) as f64;
+
+    // _NET_WORKAREA holds x, y, width, height for every desktop
+    let desktop = cardinals(xlib, display, root, "_NET_CURRENT_DESKTOP", xlib::XA_CARDINAL)
+        .and_then(|values| values.first().copied())
+        .unwrap_or(0) as usize;
+    let net_workarea = cardinals(xlib, display, root, "_NET_WORKAREA", xlib::XA_CARDINAL).and_then(|values| {
+        let area = values.get(desktop * 4..desktop * 4 + 4)?;
+        Some(Rect::new(area[0] as f64, area[1] as f64, area[2] as f64, area[3] as f64))
+    });
+
+    let clients = cardinals(xlib, display, root, "_NET_CLIENT_LIST", xlib::XA_WINDOW).unwrap_or_default();
+    let struts = clients
+        .iter()
+        .filter_map(|&window| {
+            // A client can close between listing it and reading its struts
+            let window = window as c_ulong;
+            trap_errors(xlib, display, || {
+                cardinals(xlib, display, window, "_NET_WM_STRUT_PARTIAL", xlib::XA_CARDINAL)
+                    .or_else(|| cardinals(xlib, display, window, "_NET_WM_STRUT", xlib::XA_CARDINAL))
+            })
+            .flatten()
+        })
+        .filter_map(|values| Strut::from_cardinals(&values, root_width, root_height))
+        .collect();
+
+    WorkAreas {
+        root: Some((root_width, root_height)),
+        net_workarea,
+        struts,
+    }
+}
+
+type ErrorHandler = unsafe extern "C" fn(*mut Display, *mut xlib::XErrorEvent) -> c_int;
+
+/// Xlib has a single error handler for the whole process, so traps are taken
+/// one at a time.
+static TRAP: Mutex<()> = Mutex::new(());
+/// The connection errors are trapped for, and whether one arrived
+static TRAPPED_DISPLAY: AtomicPtr<Display> = AtomicPtr::new(ptr::null_mut());
+static TRAPPED_ERROR: AtomicBool = AtomicBool::new(false);
+/// The handler installed before the trap, as a function address (0 for none)
+static PREVIOUS_HANDLER: AtomicUsize = AtomicUsize::new(0);
+
+unsafe extern "C" fn trap_error(display: *mut Display, event: *mut xlib::XErrorEvent) -> c_int {
+    if display == TRAPPED_DISPLAY.load(Ordering::SeqCst) {
+        TRAPPED_ERROR.store(true, Ordering::SeqCst);
+        return 0;
+    }
+    // Errors on other connections, such as GTK's, go where they went before
+    match PREVIOUS_HANDLER.load(Ordering::SeqCst) {
+        0 => 0,
+        address => std::mem::transmute::<usize, ErrorHandler>(address)(display, event),
+    }
+}
+
+/// Runs `f` with X errors on `display` trapped, instead of going to Xlib's
+/// default handler, which exits the process. Other clients' windows can be
+/// destroyed at any moment, so any request about one may fail with
+/// `BadWindow`. Returns `None` when a request made by `f` failed.
+pub(crate) unsafe fn trap_errors<T>(xlib: &Xlib, display: *mut Display, f: impl FnOnce() -> T) -> Option<T> {
+    let _trap = crate::lock(&TRAP);
+    // Flush earlier requests so their errors are not blamed on `f`
+    (xlib.XSync)(display, xlib::False);
+    TRAPPED_ERROR.store(false, Ordering::SeqCst);
+    TRAPPED_DISPLAY.store(display, Ordering::SeqCst);
+    let previous = (xlib.XSetErrorHandler)(Some(trap_error));
+    PREVIOUS_HANDLER.store(previous.map_or(0, |handler| handler as usize), Ordering::SeqCst);
+
+    let result = f();
+    (xlib.XSync)(display, xlib::False);
+
+    (xlib.XSetErrorHandler)(previous);
+    TRAPPED_DISPLAY.store(ptr::null_mut(), Ordering::SeqCst);
+    (!TRAPPED_ERROR.load(Ordering::SeqCst)).then_some(result)
+}
+
+/// Reads a format-32 property. Xlib returns those as an array of C longs
+/// regardless of the platform's long size.
+pub(crate) unsafe fn cardinals(xlib: &Xlib, display: *mut Display, window: c_ulong, name: &str, kind: c_ulong) -> Option<Vec<i64>> {
+    let name = CString::new(name).ok()?;
+    let atom = (xlib.XInternAtom)(display, name.as_ptr(), xlib::True);
+    if atom == 0 {
+        return None;
+    }
+
+    let mut actual_type: c_ulong = 0;
+    let mut actual_format = 0;
+    let mut item_count: c_ulong = 0;
+    let mut bytes_after: c_ulong = 0;
+    let mut data: *mut c_uchar = ptr::null_mut();
+
+    let status = (xlib.XGetWindowProperty)(
+        display,
+        window,
+        atom,
+        0,
+        MAX_ITEMS,
+        xlib::False,
+        kind,
+        &mut actual_type,
+        &mut actual_format,
+        &mut item_count,
+        &mut bytes_after,
+        &mut data,
+    );
+    if status != xlib::Success as i32 || data.is_null() {
+        return None;
+    }
+
+    let values = if actual_type == kind && actual_format == 32 {
+        let items = std::slice::from_raw_parts(data as *const c_long, item_count as usize);
+        // c_long is only 32 bits wide on some targets
+        #[allow(clippy::useless_conversion)]
+        Some(items.iter().map(|&item| i64::from(item)).collect())
+    } else {
+        None
+    };
+    (xlib.XFree)(data as *mut _);
+    values
+}
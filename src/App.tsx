@@ -1,6 +1,7 @@
 import { HashRouter, Route, Routes } from "react-router-dom";
 import { useState, useEffect, useRef } from "react";
-import { useSettings } from "./store/settings";
+import { useSettings, placementRequest, type Anchor, type Easing, type AnchorPreview, type MonitorInfo, type MonitorId, type PlacementError, type ShortcutAction, type ShortcutError } from "./store/settings";
+import type { AppState } from "./store/appState";
 import { invoke } from "@tauri-apps/api/tauri";
 import { listen } from "@tauri-apps/api/event";
 import type { WordData, WordDetailData, LearningSession } from "./types";
@@ -66,13 +67,14 @@ declare global {
 
 
 function FlashCard() {
-  const { appearance, updateAppearance, syncFromStorage, syncFromEvent } = useSettings();
+  const { appearance, updateAppearance, loadFromBackend, syncFromEvent } = useSettings();
   const [currentContentIndex, setCurrentContentIndex] = useState(0);
   const [textVisible, setTextVisible] = useState(false); // Start hidden
   const [isTransitioning, setIsTransitioning] = useState(false);
   const [renderKey, setRenderKey] = useState(0); // Force complete re-render
   const [fadeIn, setFadeIn] = useState(false); // Start faded out
   const [isInitialized, setIsInitialized] = useState(false); // Track initialization
+  const [leaderArmed, setLeaderArmed] = useState(false); // Single-key grading after the leader shortcut
   const [showTopicMenu, setShowTopicMenu] = useState(false);
   const [showFavoritesMenu, setShowFavoritesMenu] = useState(false);
   const [showTopicsSubmenu, setShowTopicsSubmenu] = useState(false);
@@ -755,43 +757,127 @@ function FlashCard() {
   
   const testContent = getLanguageWords();
   
+  // Icons, the drag handle and the topic picker stay clickable in click-through
+  // mode; the backend cuts the window's input shape down to these regions
   useEffect(() => {
-    // Don't start cycling until initialized
-    if (!isInitialized) {
+    const reportHotRegions = () => {
+      const regions = Array.from(document.querySelectorAll('[data-hot-region]')).map(element => {
+        const rect = element.getBoundingClientRect();
+        return { x: rect.left, y: rect.top, width: rect.width, height: rect.height };
+      });
+      invoke('set_hot_regions', { regions }).catch(error => console.error('Failed to register hot regions:', error));
+    };
+    
+    reportHotRegions();
+    const observer = new ResizeObserver(reportHotRegions);
+    observer.observe(document.body);
+    document.querySelectorAll('[data-hot-region]').forEach(element => observer.observe(element));
+    window.addEventListener('resize', reportHotRegions);
+    return () => {
+      observer.disconnect();
+      window.removeEventListener('resize', reportHotRegions);
+    };
+  }, [renderKey, appearance.cardSize, appearance.autoCardSize]);
+  
+  // Every card shown counts towards card-based position rotation
+  const isFirstCard = useRef(true);
+  useEffect(() => {
+    if (isFirstCard.current) {
+      isFirstCard.current = false;
+      return;
+    }
+    invoke('card_advanced').catch(error => console.error('Failed to report card advance:', error));
+  }, [currentContentIndex]);
+  
+  // Keep the backend's current card in step with the one on screen
+  const currentCardText = testContent[currentContentIndex]?.text;
+  const currentCardTranslation = currentWordDetails?.word === currentCardText ? currentWordDetails?.translation : undefined;
+  useEffect(() => {
+    if (currentCardText === undefined) {
       return;
     }
+    const card = { index: currentContentIndex, text: currentCardText, translation: currentCardTranslation ?? null };
+    invoke('set_current_card', { card })
+      .catch(error => console.error('Failed to report current card:', error));
+  }, [currentContentIndex, currentCardText, currentCardTranslation]);
+  
+  // The backend counts the deck's cards not yet known as due
+  const deck = JSON.stringify(testContent.map(card => card.text));
+  useEffect(() => {
+    invoke('set_deck', { cards: JSON.parse(deck) })
+      .catch(error => console.error('Failed to report deck:', error));
+  }, [deck]);
+  
+  // The tray lists favorite topics by code; tell it what they are called
+  const topicLabels = JSON.stringify(currentTopics.items.map(({ code, name, icon }) => ({ code, name, icon })));
+  useEffect(() => {
+    invoke('set_topic_labels', { labels: JSON.parse(topicLabels) })
+      .catch(error => console.error('Failed to report topic labels:', error));
+  }, [topicLabels]);
+  
+  // Fades the current card out and shows the card at `nextIndex`
+  const transitionToCard = (nextIndex: number) => {
+    // Start fade out
+    setFadeIn(false);
+    setTextVisible(false);
     
-    const interval = setInterval(() => {
-      // Start fade out
-      setFadeIn(false);
-      setTextVisible(false);
+    // After fade out, change content
+    setTimeout(() => {
+      setIsTransitioning(true);
+      setCurrentContentIndex(nextIndex);
+      setRenderKey(prev => prev + 1); // Force complete component re-render
+      
+      // Auto color rotation
+      if (appearance.colorRotation) {
+        const newColor = colorRotationColors[nextIndex % colorRotationColors.length];
+        updateAppearance({ textColor: newColor });
+      }
       
-      // After fade out, change content
+      // Longer pause to ensure complete clearing
       setTimeout(() => {
-        setIsTransitioning(true);
-        const nextIndex = (currentContentIndex + 1) % testContent.length;
-        setCurrentContentIndex(nextIndex);
-        setRenderKey(prev => prev + 1); // Force complete component re-render
-        
-        // Auto color rotation
-        if (appearance.colorRotation) {
-          const newColor = colorRotationColors[nextIndex % colorRotationColors.length];
-          updateAppearance({ textColor: newColor });
-        }
-        
-        // Longer pause to ensure complete clearing
+        setIsTransitioning(false);
+        setTextVisible(true);
+        // Start fade in after a longer delay
         setTimeout(() => {
-          setIsTransitioning(false);
-          setTextVisible(true);
-          // Start fade in after a longer delay
-          setTimeout(() => {
-            setFadeIn(true);
-          }, 100);
-        }, 200); // Longer clearing pause to prevent remnants
-      }, 300); // Wait for fade out
-    }, 10000); // 10 seconds display time
-    return () => clearInterval(interval);
-  }, [isInitialized, currentContentIndex, appearance.colorRotation, appearance.selectedTopicCode]); // Include language selection
+          setFadeIn(true);
+        }, 100);
+      }, 200); // Longer clearing pause to prevent remnants
+    }, 300); // Wait for fade out
+  };
+  
+  useEffect(() => {
+    // Don't start cycling until initialized
+    if (!isInitialized) {
+      return;
+    }
+    
+    // The backend times each card and asks for the next one; shortcuts can also step back or reveal
+    const unlisteners = [
+      listen('next-card', () => transitionToCard((currentContentIndex + 1) % testContent.length)),
+      listen('previous-card', () => transitionToCard((currentContentIndex - 1 + testContent.length) % testContent.length)),
+      listen('reveal-card', () => {
+        if (!showInfoPanel) {
+          handleInfoIconClick();
+          setInfoPanelFading(true);
+        } else if (cardSide === 'front') {
+          setCardSide('back');
+        } else {
+          closeInfoPanel();
+        }
+      }),
+    ];
+    return () => {
+      unlisteners.forEach(unlisten => unlisten.then(fn => fn()));
+    };
+  }, [isInitialized, currentContentIndex, appearance.colorRotation, appearance.selectedTopicCode, showInfoPanel, cardSide]); // Include language selection
+  
+  // The backend arms and disarms the leader keys; show which ones are live meanwhile
+  useEffect(() => {
+    const unlisten = listen<boolean>('leader-mode', (event) => setLeaderArmed(event.payload));
+    return () => {
+      unlisten.then(fn => fn());
+    };
+  }, []);
   
   // Topic menu stays open - only closes with X button
   // Removed auto-close behavior
@@ -805,16 +891,13 @@ function FlashCard() {
       syncFromEvent(event.payload);
     }).then(un => { unsubscribe = un; });
     
-    // Also poll storage periodically as fallback
-    const interval = setInterval(() => {
-      syncFromStorage();
-    }, 2000);
+    // Settings are owned by the backend; fetch the persisted copy once on mount
+    loadFromBackend();
     
     return () => {
       if (unsubscribe) unsubscribe();
-      clearInterval(interval);
     };
-  }, [syncFromEvent, syncFromStorage]);
+  }, [syncFromEvent, loadFromBackend]);
   
   // Cleanup hover timeout on unmount
   useEffect(() => {
@@ -911,17 +994,7 @@ function FlashCard() {
     positionUpdateTimeoutRef.current = setTimeout(async () => {
       console.log('Updating window position to grid:', appearance.positionGrid);
       try {
-        await invoke('set_window_position', {
-          gridPosition: appearance.positionGrid,
-          randomPosition: appearance.randomPosition || false,
-          manualPosition: appearance.manualPosition || false,
-          manualX: appearance.manualX || 100,
-          manualY: appearance.manualY || 100,
-          autoDetectGrid: appearance.autoDetectGrid ?? true,
-          manualGridCols: appearance.manualGridCols || 4,
-          manualGridRows: appearance.manualGridRows || 3,
-          preferredMonitor: (appearance.preferredMonitor || 'auto').toString()
-        });
+        await invoke('set_window_position', { request: placementRequest(appearance) });
         console.log('Window position updated successfully');
       } catch (error) {
         console.warn('Failed to update window position:', error);
@@ -933,7 +1006,7 @@ function FlashCard() {
         clearTimeout(positionUpdateTimeoutRef.current);
       }
     };
-  }, [appearance.positionGrid, appearance.randomPosition, appearance.manualPosition, appearance.manualX, appearance.manualY, appearance.autoDetectGrid, appearance.manualGridCols, appearance.manualGridRows, appearance.preferredMonitor]);
+  }, [appearance.positionGrid, appearance.randomPosition, appearance.manualPosition, appearance.manualX, appearance.manualY, appearance.anchorPosition, appearance.anchor, appearance.anchorOffsetX, appearance.anchorOffsetY, appearance.anchorOffsetUnit, appearance.autoDetectGrid, appearance.manualGridCols, appearance.manualGridRows, appearance.preferredMonitor]);
   
   // AI-powered learning functions
   const generateLearningWords = async () => {
@@ -943,6 +1016,8 @@ function FlashCard() {
     }
     
     setIsGeneratingWords(true);
+    invoke('set_provider_status', { status: 'generating' }).catch(console.error);
+    let providerStatus = 'offline';
     try {
       // Get language from current selection
       const language = currentTopics.items.find(item => item.code === appearance.selectedTopicCode)?.name || 'French';
@@ -977,11 +1052,13 @@ function FlashCard() {
       
       // Start the timer
       startTimer();
+      providerStatus = 'online';
       
     } catch (error) {
       console.error('Failed to generate words:', error);
     } finally {
       setIsGeneratingWords(false);
+      invoke('set_provider_status', { status: providerStatus }).catch(console.error);
     }
   };
   
@@ -1248,6 +1325,23 @@ function FlashCard() {
       overflow: "hidden", // Hide overflow only for main flashcard window
       pointerEvents: "auto" // Window is interactive, but OS-level click-through handles main card
     }}>
+      {leaderArmed && (
+        <div style={{
+          position: "fixed",
+          bottom: 4,
+          left: "50%",
+          transform: "translateX(-50%)",
+          padding: "2px 8px",
+          borderRadius: 6,
+          fontSize: 11,
+          color: "#fff",
+          backgroundColor: "rgba(0, 0, 0, 0.6)",
+          pointerEvents: "none",
+          zIndex: 1000
+        }}>
+          1 again · 2 hard · 3 good · 4 easy · Space flip · N next
+        </div>
+      )}
       <div 
         style={{
           width: (() => {
@@ -1480,6 +1574,7 @@ function FlashCard() {
             gap: 2
           }}>
           <div 
+            data-hot-region
             onClick={(e) => {
               e.stopPropagation();
               setShowTopicMenu(!showTopicMenu);
@@ -2626,6 +2721,7 @@ function FlashCard() {
         
         {/* Bottom Bar - Exact same alignment as flag */}
         <div 
+          data-hot-region
           style={{
             position: "absolute",
             bottom: -2, // Move down tiny bit more to perfect alignment with flag
@@ -2686,6 +2782,11 @@ function FlashCard() {
               
               try {
                 console.log('👍 Starting window drag...');
+                // The button usually comes up while the window manager still owns the
+                // pointer, so listen on the whole page; the backend falls back to a timeout
+                window.addEventListener('mouseup', () => {
+                  invoke('drag_ended').catch(error => console.warn('Failed to end drag:', error));
+                }, { once: true });
                 await invoke('test_drag');
                 console.log('✅ Drag initiated');
               } catch (error) {
@@ -3701,14 +3802,79 @@ function FlashCard() {
   );
 }
 
+const SHORTCUT_LABELS: Array<[ShortcutAction, string]> = [
+  ['next-card', 'Next card'],
+  ['previous-card', 'Previous card'],
+  ['reveal-card', 'Reveal / flip'],
+  ['grade-again', 'Grade: again'],
+  ['grade-hard', 'Grade: hard'],
+  ['grade-good', 'Grade: good'],
+  ['grade-easy', 'Grade: easy'],
+  ['toggle-click-through', 'Toggle click-through'],
+  ['temporary-icons', 'Show icons briefly'],
+  ['toggle-window', 'Hide / show card'],
+  ['open-settings', 'Open settings'],
+  ['pause-rotation', 'Pause / resume'],
+  ['leader', 'Leader (then 1-4, Space, N)'],
+];
+
 function Settings() {
-  const { appearance, updateAppearance, syncFromEvent } = useSettings();
+  const { appearance, updateAppearance, loadFromBackend, syncFromEvent } = useSettings();
   const [appearanceExpanded, setAppearanceExpanded] = useState(false);
   const [screenLayoutExpanded, setScreenLayoutExpanded] = useState(false);
   const [subjectsExpanded, setSubjectsExpanded] = useState(false);
   const [cardInfoExpanded, setCardInfoExpanded] = useState(false);
   const [speechExpanded, setSpeechExpanded] = useState(false);
   const [screenInfo, setScreenInfo] = useState({ width: 1920, height: 1080, cols: 4, rows: 3 });
+  const [monitors, setMonitors] = useState<MonitorInfo[]>([]);
+  const [anchorPreview, setAnchorPreview] = useState<AnchorPreview | null>(null);
+  
+  // Preview where each anchor puts the card with the current offsets
+  useEffect(() => {
+    if (!appearance.anchorPosition) return;
+    invoke('debug_anchor_positions', {
+      offsetX: appearance.anchorOffsetX,
+      offsetY: appearance.anchorOffsetY,
+      unit: appearance.anchorOffsetUnit
+    })
+      .then((preview) => setAnchorPreview(preview as AnchorPreview))
+      .catch((error) => console.warn('Failed to preview anchor positions:', (error as PlacementError).message ?? error));
+  }, [appearance.anchorPosition, appearance.anchorOffsetX, appearance.anchorOffsetY, appearance.anchorOffsetUnit]);
+  
+  // List connected monitors for the monitor preference
+  useEffect(() => {
+    invoke('get_all_monitors_info')
+      .then((info) => setMonitors(info as MonitorInfo[]))
+      .catch((error) => console.warn('Failed to list monitors:', error));
+    
+    let unsubscribe: (() => void) | undefined;
+    listen('monitor-layout-changed', () => {
+      invoke('get_all_monitors_info').then((info) => setMonitors(info as MonitorInfo[]));
+    }).then(un => { unsubscribe = un; });
+    return () => {
+      if (unsubscribe) unsubscribe();
+    };
+  }, []);
+  
+  // Select value for the monitor preference: keywords as-is, connected monitors by
+  // position in the list, matched the way the backend's `find_monitor` does so
+  // unnamed or identically named monitors stay distinguishable
+  const preferredMonitor = appearance.preferredMonitor;
+  const sameGeometry = (a: MonitorId, b: MonitorId) =>
+    a.x === b.x && a.y === b.y && a.width === b.width && a.height === b.height;
+  const preferredMonitorIndex = typeof preferredMonitor === 'object'
+    ? [
+        monitors.findIndex((m) => m.id.name === preferredMonitor.name && sameGeometry(m.id, preferredMonitor)),
+        preferredMonitor.name ? monitors.findIndex((m) => m.id.name === preferredMonitor.name) : -1,
+        monitors.findIndex((m) => (!preferredMonitor.name || !m.id.name) && sameGeometry(m.id, preferredMonitor)),
+      ].find((index) => index >= 0) ?? -1
+    : typeof preferredMonitor === 'number' && monitors[preferredMonitor] ? preferredMonitor : -1;
+  const preferredMonitorMissing = typeof preferredMonitor === 'object' && preferredMonitorIndex < 0;
+  const preferredMonitorValue = preferredMonitorIndex >= 0
+    ? `monitor:${preferredMonitorIndex}`
+    : preferredMonitorMissing
+      ? 'monitor:missing'
+      : typeof preferredMonitor === 'number' ? 'auto' : preferredMonitor;
   
   // Get screen info on mount and when grid settings change
   useEffect(() => {
@@ -3737,10 +3903,47 @@ function Settings() {
       // @ts-ignore
       syncFromEvent(event.payload);
     }).then(un => { unsubscribe = un; });
+    loadFromBackend();
     return () => {
       if (unsubscribe) unsubscribe();
     };
-  }, [syncFromEvent]);
+  }, [syncFromEvent, loadFromBackend]);
+  
+  // Bindings the backend could not register, e.g. because another app owns them
+  const [shortcutErrors, setShortcutErrors] = useState<ShortcutError[]>([]);
+  useEffect(() => {
+    invoke<ShortcutError[]>('get_shortcut_errors')
+      .then(setShortcutErrors)
+      .catch(error => console.warn('Failed to load shortcut errors:', error));
+    const unlisten = listen<ShortcutError[]>('shortcut-errors', (event) => setShortcutErrors(event.payload));
+    return () => {
+      unlisten.then(fn => fn());
+    };
+  }, []);
+  
+  // Click-through can also change from the global shortcut, so follow the backend
+  // Rejected bindings come back as diagnostics and keep the old binding active
+  const rebindShortcut = (action: ShortcutAction, accelerator: string) => {
+    invoke('rebind_shortcut', { action, accelerator })
+      .then(() => setShortcutErrors(errors => errors.filter(e => e.action !== action)))
+      .catch((errors: ShortcutError[] | string) => {
+        const diagnostics = Array.isArray(errors)
+          ? errors
+          : [{ action, accelerator, kind: 'unavailable' as const, message: String(errors) }];
+        setShortcutErrors(current => [...current.filter(e => e.action !== action), ...diagnostics]);
+      });
+  };
+
+  const [clickThrough, setClickThrough] = useState(false);
+  useEffect(() => {
+    invoke<AppState>('get_app_state')
+      .then(state => setClickThrough(state.clickThrough))
+      .catch(error => console.warn('Failed to load click-through mode:', error));
+    const unlisten = listen<boolean>('click-through-toggled', (event) => setClickThrough(event.payload));
+    return () => {
+      unlisten.then(fn => fn());
+    };
+  }, []);
   
   // Close all panels when component unmounts (settings window closes)
   useEffect(() => {
@@ -4910,8 +5113,16 @@ function Settings() {
               <div style={{ marginBottom: 20 }}>
                 <label style={{ display: 'block', marginBottom: 8 }}>Flashcard Monitor Preference</label>
                 <select 
-                  value={appearance.preferredMonitor}
-                  onChange={(e) => updateAppearance({ preferredMonitor: e.target.value as any })}
+                  value={preferredMonitorValue}
+                  onChange={(e) => {
+                    const value = e.target.value;
+                    if (value.startsWith('monitor:')) {
+                      const monitor = monitors[Number(value.slice('monitor:'.length))];
+                      if (monitor) updateAppearance({ preferredMonitor: monitor.id });
+                    } else {
+                      updateAppearance({ preferredMonitor: value as 'auto' | 'primary' | 'current' });
+                    }
+                  }}
                   style={{
                     backgroundColor: appearance.darkMode ? '#2a2a2a' : '#f5f5f5',
                     color: appearance.darkMode ? '#ffffff' : '#000000',
@@ -4925,13 +5136,27 @@ function Settings() {
                   <option value="auto">Auto (Use current monitor)</option>
                   <option value="primary">Always use primary monitor</option>
                   <option value="current">Stay on current monitor</option>
-                  <option value="0">Monitor 1 (Built-in)</option>
-                  <option value="1">Monitor 2 (External)</option>
-                  <option value="2">Monitor 3 (Sidecar/External)</option>
+                  {monitors.map((monitor, index) => (
+                    <option key={monitor.index} value={`monitor:${index}`}>
+                      {monitor.id.name || `Monitor ${monitor.index + 1}`} ({monitor.id.width}x{monitor.id.height})
+                      {monitor.isPrimary ? ' - Primary' : ''}
+                    </option>
+                  ))}
+                  {preferredMonitorMissing && typeof preferredMonitor === 'object' && (
+                    <option value="monitor:missing">
+                      {preferredMonitor.name || 'Unnamed monitor'} (disconnected)
+                    </option>
+                  )}
                 </select>
                 <div style={{ fontSize: 12, color: appearance.darkMode ? '#999' : '#666', marginTop: 4 }}>
                   Choose which monitor the flashcard should appear on. Supports external monitors and iPad Sidecar.
                 </div>
+                {monitors.filter((monitor) => monitor.isCurrent).map((monitor) => (
+                  <div key={monitor.index} style={{ fontSize: 12, color: appearance.darkMode ? '#999' : '#666', marginTop: 4 }}>
+                    Usable area: {monitor.workArea.width}x{monitor.workArea.height} at ({monitor.workArea.x}, {monitor.workArea.y})
+                    {(monitor.workArea.width !== monitor.id.width || monitor.workArea.height !== monitor.id.height) && ' (excluding panels and docks)'}
+                  </div>
+                ))}
               </div>
         
               {/* Grid Detection Settings */}
@@ -5110,8 +5335,103 @@ function Settings() {
             Window will always open where you drag it
           </div>
           
-          {/* Random Position Toggle - only show if not manual */}
-          {!appearance.manualPosition && (
+          {/* Anchor Position Toggle - only show if not manual or random */}
+          {!appearance.manualPosition && !appearance.randomPosition && (
+            <label style={{ display: 'flex', alignItems: 'center', gap: 8, marginBottom: 12 }}>
+              <input 
+                type="checkbox" 
+                checked={appearance.anchorPosition}
+                onChange={(e) => updateAppearance({ anchorPosition: e.target.checked })}
+              />
+              Pin to Corner or Edge
+            </label>
+          )}
+          
+          {/* Anchor Settings */}
+          {appearance.anchorPosition && !appearance.manualPosition && (
+            <div style={{ marginBottom: 12 }}>
+              <div style={{
+                display: 'grid',
+                gridTemplateColumns: 'repeat(3, 1fr)',
+                gap: 4,
+                maxWidth: 135,
+                marginBottom: 8
+              }}>
+                {(['top-left', 'top', 'top-right', 'left', 'center', 'right', 'bottom-left', 'bottom', 'bottom-right'] as Anchor[]).map((anchor) => (
+                  <button
+                    key={anchor}
+                    title={anchor}
+                    onClick={() => updateAppearance({ anchor })}
+                    style={{
+                      width: 40,
+                      height: 30,
+                      border: appearance.anchor === anchor
+                        ? `2px solid ${appearance.darkMode ? '#0088ff' : '#007bff'}`
+                        : `1px solid ${appearance.darkMode ? '#555' : '#ccc'}`,
+                      backgroundColor: appearance.anchor === anchor
+                        ? (appearance.darkMode ? '#0066cc' : '#e3f2fd')
+                        : (appearance.darkMode ? '#333' : '#fff'),
+                      borderRadius: 2,
+                      cursor: 'pointer'
+                    }}
+                  />
+                ))}
+              </div>
+              <div style={{ display: 'flex', alignItems: 'center', gap: 8, fontSize: 12, marginBottom: 8 }}>
+                <label>
+                  X <input
+                    type="number"
+                    value={appearance.anchorOffsetX}
+                    onChange={(e) => updateAppearance({ anchorOffsetX: Number(e.target.value) || 0 })}
+                    style={{ width: 60 }}
+                  />
+                </label>
+                <label>
+                  Y <input
+                    type="number"
+                    value={appearance.anchorOffsetY}
+                    onChange={(e) => updateAppearance({ anchorOffsetY: Number(e.target.value) || 0 })}
+                    style={{ width: 60 }}
+                  />
+                </label>
+                <select
+                  value={appearance.anchorOffsetUnit}
+                  onChange={(e) => updateAppearance({ anchorOffsetUnit: e.target.value as 'px' | 'percent' })}
+                >
+                  <option value="px">px</option>
+                  <option value="percent">%</option>
+                </select>
+              </div>
+              {anchorPreview && (() => {
+                // Scale the monitor's work area down to a 200px wide preview
+                const scale = 200 / anchorPreview.area.width;
+                const position = anchorPreview.positions.find(([anchor]) => anchor === appearance.anchor);
+                return (
+                  <div style={{
+                    position: 'relative',
+                    width: anchorPreview.area.width * scale,
+                    height: anchorPreview.area.height * scale,
+                    border: `1px solid ${appearance.darkMode ? '#444' : '#ddd'}`,
+                    backgroundColor: appearance.darkMode ? '#2a2a2a' : '#f9f9f9'
+                  }}>
+                    {position && (
+                      <div style={{
+                        position: 'absolute',
+                        left: (position[1] - anchorPreview.area.x) * scale,
+                        top: (position[2] - anchorPreview.area.y) * scale,
+                        width: anchorPreview.window.width * scale,
+                        height: anchorPreview.window.height * scale,
+                        backgroundColor: appearance.darkMode ? '#0066cc' : '#90caf9'
+                      }} />
+                    )}
+                  </div>
+                );
+              })()}
+            </div>
+          )}
+          
+          {/* Random Position Toggle - only show if not manual or anchored */}
+          {!appearance.manualPosition && !appearance.anchorPosition && (
             <label style={{ display: 'flex', alignItems: 'center', gap: 8, marginBottom: 12 }}>
               <input 
                 type="checkbox" 
@@ -5124,15 +5444,7 @@ function Settings() {
                   if (isRandom) {
                     try {
                       await invoke('set_window_position', {
-                        gridPosition: appearance.positionGrid,
-                        randomPosition: true,
-                        manualPosition: false,
-                        manualX: appearance.manualX,
-                        manualY: appearance.manualY,
-                        autoDetectGrid: appearance.autoDetectGrid,
-                        manualGridCols: appearance.manualGridCols,
-                        manualGridRows: appearance.manualGridRows,
-                        preferredMonitor: appearance.preferredMonitor.toString()
+                        request: placementRequest({ ...appearance, randomPosition: true, manualPosition: false })
                       });
                     } catch (error) {
                       console.warn('Failed to set random position:', error);
@@ -5144,8 +5456,8 @@ function Settings() {
             </label>
           )}
           
-          {/* Grid Position - only show if not random and not manual */}
-          {!appearance.randomPosition && !appearance.manualPosition && (
+          {/* Grid Position - only show if not random, manual or anchored */}
+          {!appearance.randomPosition && !appearance.manualPosition && !appearance.anchorPosition && (
             <div>
               <div style={{ fontSize: 12, color: appearance.darkMode ? '#999' : '#666', marginBottom: 8 }}>
                 Screen: {screenInfo.width}x{screenInfo.height} ({screenInfo.cols}x{screenInfo.rows} grid)
@@ -5199,19 +5511,11 @@ function Settings() {
                       // This works even when main window isn't actively running the position effect
                       try {
                         await invoke('set_window_position', {
-                          gridPosition: index,
-                          randomPosition: appearance.randomPosition || false,
-                          manualPosition: appearance.manualPosition || false,
-                          manualX: appearance.manualX || 100,
-                          manualY: appearance.manualY || 100,
-                          autoDetectGrid: appearance.autoDetectGrid ?? true,
-                          manualGridCols: appearance.manualGridCols || 4,
-                          manualGridRows: appearance.manualGridRows || 3,
-                          preferredMonitor: (appearance.preferredMonitor || 'auto').toString()
+                          request: placementRequest({ ...appearance, positionGrid: index })
                         });
                         console.log('Grid position applied successfully to main window');
                       } catch (error) {
-                        console.error('Failed to apply grid position:', error);
+                        console.error('Failed to apply grid position:', (error as PlacementError).message ?? error);
                       }
                     }}
                     style={{
@@ -5248,6 +5552,250 @@ function Settings() {
               </div>
             </div>
           )}
+          
+          {/* Position Rotation - moves the card now and then so it stays noticeable */}
+          <div style={{ marginTop: 16 }}>
+            <label style={{ display: 'flex', alignItems: 'center', gap: 8, marginBottom: 8 }}>
+              <input
+                type="checkbox"
+                checked={appearance.positionRotation}
+                onChange={(e) => updateAppearance({ positionRotation: e.target.checked })}
+              />
+              Rotate Position
+            </label>
+            {appearance.positionRotation && (
+              <div style={{ marginLeft: 24, fontSize: 12 }}>
+                {(appearance.manualPosition || appearance.anchorPosition) && (
+                  <div style={{ color: appearance.darkMode ? '#999' : '#666', marginBottom: 8 }}>
+                    Paused while the card is pinned or at a dragged position
+                  </div>
+                )}
+                <div style={{ display: 'flex', alignItems: 'center', gap: 8, marginBottom: 8 }}>
+                  Every
+                  <input
+                    type="number"
+                    min={1}
+                    max={1440}
+                    value={appearance.rotationInterval}
+                    onChange={(e) => updateAppearance({ rotationInterval: Math.max(1, parseInt(e.target.value) || 1) })}
+                    style={{ width: 60 }}
+                  />
+                  <select
+                    value={appearance.rotationTrigger}
+                    onChange={(e) => updateAppearance({ rotationTrigger: e.target.value as 'cards' | 'minutes' })}
+                  >
+                    <option value="cards">cards</option>
+                    <option value="minutes">minutes</option>
+                  </select>
+                </div>
+                <div style={{ display: 'flex', alignItems: 'center', gap: 8, marginBottom: 8 }}>
+                  Avoid the last
+                  <input
+                    type="number"
+                    min={0}
+                    max={16}
+                    value={appearance.rotationAvoidRecent}
+                    onChange={(e) => updateAppearance({ rotationAvoidRecent: Math.max(0, parseInt(e.target.value) || 0) })}
+                    style={{ width: 60 }}
+                  />
+                  positions
+                </div>
+                <label style={{ display: 'flex', alignItems: 'center', gap: 8, marginBottom: 8 }}>
+                  <input
+                    type="checkbox"
+                    checked={appearance.rotationAnimate}
+                    onChange={(e) => updateAppearance({ rotationAnimate: e.target.checked })}
+                  />
+                  Slide between positions
+                </label>
+                {!appearance.randomPosition && (
+                  <div>
+                    <div style={{ color: appearance.darkMode ? '#999' : '#666', marginBottom: 4 }}>
+                      Never rotate to (click to exclude):
+                    </div>
+                    <div style={{ display: 'grid', gridTemplateColumns: `repeat(${screenInfo.cols}, 1fr)`, gap: 4, maxWidth: Math.min(300, screenInfo.cols * 45) }}>
+                      {Array.from({ length: screenInfo.cols * screenInfo.rows }, (_, index) => {
+                        const excluded = appearance.rotationExcludedSlots.includes(index);
+                        return (
+                          <button
+                            key={index}
+                            onClick={() => updateAppearance({
+                              rotationExcludedSlots: excluded
+                                ? appearance.rotationExcludedSlots.filter(slot => slot !== index)
+                                : [...appearance.rotationExcludedSlots, index]
+                            })}
+                            style={{
+                              width: 40,
+                              height: 30,
+                              border: `1px solid ${appearance.darkMode ? '#555' : '#ccc'}`,
+                              backgroundColor: excluded
+                                ? (appearance.darkMode ? '#662222' : '#ffcdd2')
+                                : (appearance.darkMode ? '#333' : '#fff'),
+                              borderRadius: 2,
+                              cursor: 'pointer',
+                              fontSize: 10,
+                              color: appearance.darkMode ? '#ccc' : '#666',
+                              textDecoration: excluded ? 'line-through' : 'none'
+                            }}
+                          >
+                            {index + 1}
+                          </button>
+                        );
+                      })}
+                    </div>
+                  </div>
+                )}
+              </div>
+            )}
+          </div>
+          
+          {/* Snapping when the card is dropped after a drag */}
+          <div style={{ marginTop: 16 }}>
+            <label style={{ display: 'flex', alignItems: 'center', gap: 8, marginBottom: 8 }}>
+              <input
+                type="checkbox"
+                checked={appearance.snapOnDrop}
+                onChange={(e) => updateAppearance({ snapOnDrop: e.target.checked })}
+              />
+              Snap to Edges and Grid When Dropped
+            </label>
+            {appearance.snapOnDrop && (
+              <div style={{ marginLeft: 24, fontSize: 12 }}>
+                <div style={{ display: 'flex', alignItems: 'center', gap: 8, marginBottom: 8 }}>
+                  Distance
+                  <input
+                    type="range"
+                    min={4}
+                    max={100}
+                    value={appearance.snapThreshold}
+                    onChange={(e) => updateAppearance({ snapThreshold: parseInt(e.target.value) })}
+                  />
+                  {appearance.snapThreshold}px
+                </div>
+                <label style={{ display: 'flex', alignItems: 'center', gap: 8 }}>
+                  <input
+                    type="checkbox"
+                    checked={appearance.snapToWindows}
+                    onChange={(e) => updateAppearance({ snapToWindows: e.target.checked })}
+                  />
+                  Dock against other Floatlearn windows
+                </label>
+              </div>
+            )}
+          </div>
+          
+          {/* Click-through - the backend applies it and broadcasts click-through-toggled */}
+          <div style={{ marginTop: 16 }}>
+            <label style={{ display: 'flex', alignItems: 'center', gap: 8, marginBottom: 8 }}>
+              <input
+                type="checkbox"
+                checked={clickThrough}
+                onChange={(e) => {
+                  invoke('set_click_through', { clickThrough: e.target.checked })
+                    .catch(error => console.error('Failed to change click-through mode:', error));
+                }}
+              />
+              Click-Through
+            </label>
+            <div style={{ marginLeft: 24, fontSize: 12, color: appearance.darkMode ? '#999' : '#666' }}>
+              Clicks pass through the card except on its icons. Toggle anywhere with Ctrl/Cmd+Shift+K
+            </div>
+          </div>
+          
+          {/* Global shortcuts - checked and registered by the backend on blur, saved only if that works */}
+          <div style={{ marginTop: 16 }}>
+            <div style={{ fontWeight: 'bold', marginBottom: 8 }}>Shortcuts</div>
+            {SHORTCUT_LABELS.map(([action, label]) => {
+              const error = shortcutErrors.find(e => e.action === action);
+              return (
+                <div key={action} style={{ marginLeft: 24, fontSize: 12, marginBottom: 4 }}>
+                  <label style={{ display: 'flex', alignItems: 'center', gap: 8 }}>
+                    <span style={{ width: 140 }}>{label}</span>
+                    <input
+                      key={appearance.shortcuts[action]}
+                      type="text"
+                      defaultValue={appearance.shortcuts[action]}
+                      placeholder="Unbound"
+                      onBlur={(e) => {
+                        if (e.target.value !== appearance.shortcuts[action]) {
+                          rebindShortcut(action, e.target.value);
+                        }
+                      }}
+                    />
+                  </label>
+                  {error && (
+                    <div style={{ marginLeft: 148, color: '#d9534f' }}>
+                      {error.accelerator || 'Shortcut'}: {error.message}
+                    </div>
+                  )}
+                </div>
+              );
+            })}
+          </div>
+          
+          {/* Dodge mode - X11 only, the backend reports when it is unavailable */}
+          <div style={{ marginTop: 16 }}>
+            <label style={{ display: 'flex', alignItems: 'center', gap: 8, marginBottom: 8 }}>
+              <input
+                type="checkbox"
+                checked={appearance.dodgeMode}
+                onChange={(e) => updateAppearance({ dodgeMode: e.target.checked })}
+              />
+              Move Out of the Way (Linux/X11)
+            </label>
+            {appearance.dodgeMode && (
+              <div style={{ marginLeft: 24, fontSize: 12 }}>
+                <div style={{ display: 'flex', alignItems: 'center', gap: 8, marginBottom: 4 }}>
+                  Keep
+                  <input
+                    type="range"
+                    min={0}
+                    max={100}
+                    value={appearance.dodgeMargin}
+                    onChange={(e) => updateAppearance({ dodgeMargin: parseInt(e.target.value) })}
+                  />
+                  {appearance.dodgeMargin}px away
+                </div>
+                <div style={{ color: appearance.darkMode ? '#999' : '#666' }}>
+                  Avoids the focused window, and the mouse pointer while click-through is on
+                </div>
+              </div>
+            )}
+          </div>
+          
+          {/* Movement animation for repositioning, rotation and snapping */}
+          <div style={{ marginTop: 16 }}>
+            <label style={{ display: 'flex', alignItems: 'center', gap: 8, marginBottom: 8 }}>
+              <input
+                type="checkbox"
+                checked={appearance.smoothMovement}
+                onChange={(e) => updateAppearance({ smoothMovement: e.target.checked })}
+              />
+              Smooth Movement
+            </label>
+            {appearance.smoothMovement && (
+              <div style={{ marginLeft: 24, fontSize: 12, display: 'flex', alignItems: 'center', gap: 8 }}>
+                <input
+                  type="range"
+                  min={100}
+                  max={3000}
+                  step={50}
+                  value={appearance.movementDurationMs}
+                  onChange={(e) => updateAppearance({ movementDurationMs: parseInt(e.target.value) })}
+                />
+                {appearance.movementDurationMs}ms
+                <select
+                  value={appearance.movementEasing}
+                  onChange={(e) => updateAppearance({ movementEasing: e.target.value as Easing })}
+                >
+                  <option value="ease-in-out">Ease in and out</option>
+                  <option value="ease-out">Ease out</option>
+                  <option value="ease-in">Ease in</option>
+                  <option value="linear">Linear</option>
+                </select>
+              </div>
+            )}
+          </div>
         </div>
             </div>
           )}
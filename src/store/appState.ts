@@ -0,0 +1,46 @@
+import { create } from 'zustand';
+import { invoke } from '@tauri-apps/api/tauri';
+import { listen } from '@tauri-apps/api/event';
+
+export type ProviderStatus = 'unknown' | 'online' | 'offline' | 'generating';
+
+export type WindowMode = 'visible' | 'hidden';
+
+export type Grade = 'again' | 'hard' | 'good' | 'easy';
+
+// Mirrors the backend AppState broadcast with `state-changed`
+export interface AppState {
+  clickThrough: boolean;
+  paused: boolean;
+  revealUntil: number | null; // Unix time in milliseconds
+  session: {
+    startedAt: number;
+    cardsSeen: number;
+    grades: Record<Grade, number>;
+    streak: number; // Grades in a row without "again"
+    deck: string[];
+    learned: string[]; // Cards in the deck not in here are due
+  };
+  currentCard: { index: number; text: string; translation: string | null } | null;
+  providerStatus: ProviderStatus;
+  windowMode: WindowMode;
+}
+
+interface AppStateStore {
+  state: AppState | null;
+  // Loads the current state and follows its changes; returns the unsubscribe function
+  watch: () => () => void;
+}
+
+export const useAppState = create<AppStateStore>()((set) => ({
+  state: null,
+  watch: () => {
+    const unlisten = listen<AppState>('state-changed', (event) => set({ state: event.payload }));
+    invoke<AppState>('get_app_state')
+      .then((state) => set({ state }))
+      .catch((e) => console.warn('Failed to load app state from backend', e));
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  },
+}));
@@ -1,6 +1,5 @@
 import { create } from 'zustand';
-import { persist } from 'zustand/middleware';
-import { emit } from '@tauri-apps/api/event';
+import { invoke } from '@tauri-apps/api/tauri';
 
 export interface AppearanceSettings {
   // Font settings
@@ -29,6 +28,9 @@ export interface AppearanceSettings {
   // Multi-space settings (macOS)
   showOnAllSpaces: boolean;
   
+  // Main card ignores mouse events
+  clickThrough: boolean;
+  
   // Position settings
   positionGrid: number; // index for grid positions based on screen size
   randomPosition: boolean;
@@ -36,6 +38,38 @@ export interface AppearanceSettings {
   manualX: number; // stored manual position
   manualY: number;
   
+  // Anchor placement: pinned to a corner/edge, offset inwards
+  anchorPosition: boolean;
+  anchor: Anchor;
+  anchorOffsetX: number;
+  anchorOffsetY: number;
+  anchorOffsetUnit: 'px' | 'percent';
+  
+  // Position rotation: move the card every N cards or minutes
+  positionRotation: boolean;
+  rotationTrigger: 'cards' | 'minutes';
+  rotationInterval: number;
+  rotationAvoidRecent: number; // how many previous slots to avoid
+  rotationExcludedSlots: number[];
+  rotationAnimate: boolean;
+  
+  // How the card moves when it is repositioned
+  smoothMovement: boolean;
+  movementDurationMs: number;
+  movementEasing: Easing;
+  
+  // Snapping when the card is dropped after a drag
+  snapOnDrop: boolean;
+  snapThreshold: number; // logical pixels
+  snapToWindows: boolean;
+  
+  // Dodge mode (Linux/X11): move away from the pointer and the focused window
+  dodgeMode: boolean;
+  dodgeMargin: number; // logical pixels
+  
+  // Global shortcut per action; an empty string leaves the action unbound
+  shortcuts: Record<ShortcutAction, string>;
+  
   // Grid override settings
   autoDetectGrid: boolean;
   manualGridInputType: 'grid' | 'screen';
@@ -47,7 +81,7 @@ export interface AppearanceSettings {
   manualScreenHeight: number;
   
   // Monitor settings
-  preferredMonitor: 'auto' | 'primary' | 'current' | number; // auto, primary, current, or monitor index
+  preferredMonitor: 'auto' | 'primary' | 'current' | number | MonitorId; // auto, primary, current, a specific monitor, or a legacy index
   allowCrossMonitorPositioning: boolean; // allow settings window on different monitor
   
   // Topic settings (flexible for languages, subjects, etc.)
@@ -72,12 +106,116 @@ export interface AppearanceSettings {
   cardInfoShowPronunciation: boolean;
   cardInfoMaxExamples: number;
   cardInfoCloseBehavior: 'manual' | 'mouse-leave' | 'both';
+  
+  // AI provider settings (per profile)
+  aiProvider: string;
+  aiEndpoint: string;
+  aiModel: string;
+}
+
+// Identifies a monitor by name and geometry, so it survives reordering
+export interface MonitorId {
+  name: string;
+  x: number;
+  y: number;
+  width: number;
+  height: number;
+}
+
+// Returned by `get_all_monitors_info`
+export interface MonitorInfo {
+  index: number;
+  id: MonitorId;
+  scaleFactor: number;
+  // Usable area excluding panels and docks, in physical pixels
+  workArea: { x: number; y: number; width: number; height: number };
+  isPrimary: boolean;
+  isCurrent: boolean;
+}
+
+export interface ProfilesInfo {
+  active: string;
+  names: string[];
+}
+
+export type ShortcutAction =
+  | 'next-card' | 'previous-card' | 'reveal-card'
+  | 'grade-again' | 'grade-hard' | 'grade-good' | 'grade-easy'
+  | 'toggle-click-through' | 'temporary-icons' | 'toggle-window'
+  | 'open-settings' | 'pause-rotation' | 'leader';
+
+export type ShortcutProblemKind = 'invalidSyntax' | 'unknownKey' | 'missingModifier' | 'duplicate' | 'unavailable';
+
+// Returned by `get_shortcut_errors`, `check_keymap` and `rebind_shortcut`, and broadcast as `shortcut-errors`
+export interface ShortcutError {
+  action: ShortcutAction;
+  accelerator: string;
+  kind: ShortcutProblemKind;
+  message: string;
+  other?: ShortcutAction; // The action holding the same keys, for duplicates
 }
 
+export type Easing = 'linear' | 'ease-in' | 'ease-out' | 'ease-in-out';
+
+export type Anchor =
+  | 'top-left' | 'top' | 'top-right'
+  | 'left' | 'center' | 'right'
+  | 'bottom-left' | 'bottom' | 'bottom-right';
+
+type MonitorPreference = AppearanceSettings['preferredMonitor'];
+
+// Mirrors the backend PlacementRequest passed to `set_window_position`
+export type PlacementRequest =
+  | { mode: 'grid'; slot: number; autoDetectGrid: boolean; cols: number; rows: number; monitor: MonitorPreference }
+  | { mode: 'random'; monitor: MonitorPreference }
+  | { mode: 'manual'; x: number; y: number }
+  | { mode: 'anchor'; anchor: Anchor; offsetX: number; offsetY: number; unit: 'px' | 'percent'; monitor: MonitorPreference };
+
+// Returned by `debug_anchor_positions`; all values are logical pixels
+export interface AnchorPreview {
+  area: { x: number; y: number; width: number; height: number };
+  window: { width: number; height: number };
+  positions: Array<[Anchor, number, number]>;
+}
+
+// Error returned by placement commands
+export interface PlacementError {
+  kind: 'invalidGrid' | 'invalidSlot' | 'invalidCoordinate' | 'windowUnavailable';
+  message: string;
+}
+
+export const placementRequest = (appearance: AppearanceSettings): PlacementRequest => {
+  const monitor = appearance.preferredMonitor ?? 'auto';
+  if (appearance.manualPosition) {
+    return { mode: 'manual', x: appearance.manualX, y: appearance.manualY };
+  }
+  if (appearance.anchorPosition) {
+    return {
+      mode: 'anchor',
+      anchor: appearance.anchor,
+      offsetX: appearance.anchorOffsetX,
+      offsetY: appearance.anchorOffsetY,
+      unit: appearance.anchorOffsetUnit,
+      monitor,
+    };
+  }
+  if (appearance.randomPosition) {
+    return { mode: 'random', monitor };
+  }
+  return {
+    mode: 'grid',
+    slot: appearance.positionGrid,
+    autoDetectGrid: appearance.autoDetectGrid ?? true,
+    cols: appearance.manualGridCols,
+    rows: appearance.manualGridRows,
+    monitor,
+  };
+};
+
 interface SettingsStore {
   appearance: AppearanceSettings;
   updateAppearance: (settings: Partial<AppearanceSettings>) => void;
-  syncFromStorage: () => void;
+  loadFromBackend: () => Promise<void>;
   syncFromEvent: (settings: AppearanceSettings) => void;
 }
 
@@ -96,11 +234,46 @@ const defaultAppearance: AppearanceSettings = {
   darkMode: false,
   syncSymbolColor: false,
   showOnAllSpaces: true,
+  clickThrough: false,
   positionGrid: 4, // center-left position
   randomPosition: false,
   manualPosition: false,
   manualX: 100,
   manualY: 100,
+  anchorPosition: false,
+  anchor: 'bottom-right',
+  anchorOffsetX: 24,
+  anchorOffsetY: 24,
+  anchorOffsetUnit: 'px',
+  positionRotation: false,
+  rotationTrigger: 'minutes',
+  rotationInterval: 15,
+  rotationAvoidRecent: 3,
+  rotationExcludedSlots: [],
+  rotationAnimate: true,
+  smoothMovement: true,
+  movementDurationMs: 350,
+  movementEasing: 'ease-in-out',
+  snapOnDrop: true,
+  snapThreshold: 24,
+  snapToWindows: false,
+  dodgeMode: false,
+  dodgeMargin: 16,
+  shortcuts: {
+    'next-card': 'CmdOrCtrl+Shift+Right',
+    'previous-card': 'CmdOrCtrl+Shift+Left',
+    'reveal-card': 'CmdOrCtrl+Shift+Down',
+    'grade-again': '',
+    'grade-hard': '',
+    'grade-good': '',
+    'grade-easy': '',
+    'toggle-click-through': 'CmdOrCtrl+Shift+K',
+    'temporary-icons': 'CmdOrCtrl+Shift+I',
+    'toggle-window': 'CmdOrCtrl+Shift+H',
+    'open-settings': 'CmdOrCtrl+Shift+Comma',
+    'pause-rotation': 'CmdOrCtrl+Shift+P',
+    'leader': '',
+  },
   autoDetectGrid: true,
   manualGridInputType: 'grid',
   manualGridCols: 4,
@@ -128,66 +301,70 @@ const defaultAppearance: AppearanceSettings = {
   cardInfoShowPronunciation: false,
   cardInfoMaxExamples: 2,
   cardInfoCloseBehavior: 'both',
+  
+  aiProvider: 'ollama',
+  aiEndpoint: 'http://localhost:11434',
+  aiModel: 'llama3',
 };
 
 console.log('Default appearance settings:', defaultAppearance);
 
-// Debounce function to prevent rapid updates
-let debounceTimeout: number | undefined;
-
-export const useSettings = create<SettingsStore>()(
-  persist(
-    (set, get) => ({
-      appearance: defaultAppearance,
-      updateAppearance: async (newSettings) => {
-        console.log('Settings store updating appearance:', newSettings);
-        const updatedAppearance = { ...get().appearance, ...newSettings };
-        set(() => ({ appearance: updatedAppearance }));
-        
-        // Debounce the event emission to prevent rapid fire
-        if (debounceTimeout) clearTimeout(debounceTimeout);
-        debounceTimeout = setTimeout(async () => {
-          try {
-            console.log('Emitting settings-updated event:', updatedAppearance);
-            await emit('settings-updated', updatedAppearance);
-            console.log('Settings event emitted successfully');
-          } catch (error) {
-            console.warn('Event emit failed, relying on storage sync:', error);
-          }
-        }, 100); // 100ms debounce
-      },
-      syncFromStorage: () => {
-        try {
-          const raw = localStorage.getItem('floatlearn-settings');
-          if (!raw) return;
-          const parsed = JSON.parse(raw);
-          if (parsed?.state?.appearance) {
-            const currentAppearance = get().appearance;
-            // Simple shallow comparison to prevent unnecessary updates
-            const hasChanged = Object.keys(parsed.state.appearance).some(key => 
-              currentAppearance[key as keyof AppearanceSettings] !== parsed.state.appearance[key]
-            );
-            if (hasChanged) {
-              set(() => ({ appearance: { ...parsed.state.appearance } }), false); // false = no persist
-            }
-          }
-        } catch (e) {
-          console.warn('Failed to sync settings from storage', e);
-        }
-      },
-      syncFromEvent: (settings) => {
-        const currentAppearance = get().appearance;
-        // Simple shallow comparison to prevent unnecessary updates
-        const hasChanged = Object.keys(settings).some(key => 
-          currentAppearance[key as keyof AppearanceSettings] !== settings[key as keyof AppearanceSettings]
-        );
-        if (hasChanged) {
-          set(() => ({ appearance: { ...settings } }), false); // false = no persist
-        }
-      },
-    }),
-    {
-      name: 'floatlearn-settings',
+// Settings used to be persisted by zustand under this key; they now live in the
+// backend config file and this blob is only read once to migrate it.
+const LEGACY_STORAGE_KEY = 'floatlearn-settings';
+
+async function migrateLegacySettings(): Promise<AppearanceSettings | null> {
+  try {
+    const raw = localStorage.getItem(LEGACY_STORAGE_KEY);
+    if (!raw) return null;
+    const parsed = JSON.parse(raw);
+    if (!parsed?.state?.appearance) {
+      localStorage.removeItem(LEGACY_STORAGE_KEY);
+      return null;
+    }
+    console.log('Migrating legacy localStorage settings to backend');
+    // Imported field by field, so stale keys are skipped rather than failing the lot;
+    // the blob is only dropped once the backend has saved it
+    const settings = await invoke<AppearanceSettings>('import_legacy_settings', { stored: parsed.state.appearance });
+    localStorage.removeItem(LEGACY_STORAGE_KEY);
+    return settings;
+  } catch (e) {
+    console.warn('Failed to migrate legacy settings', e);
+    return null;
+  }
+}
+
+export const useSettings = create<SettingsStore>()((set, get) => ({
+  appearance: defaultAppearance,
+  updateAppearance: async (newSettings) => {
+    console.log('Settings store updating appearance:', newSettings);
+    // Apply optimistically; the backend broadcasts the saved result to every window
+    set(() => ({ appearance: { ...get().appearance, ...newSettings } }));
+    try {
+      const saved = await invoke<AppearanceSettings>('update_settings', { patch: newSettings });
+      get().syncFromEvent(saved);
+    } catch (error) {
+      console.warn('Failed to save settings, reloading from backend:', error);
+      await get().loadFromBackend();
+    }
+  },
+  loadFromBackend: async () => {
+    try {
+      const migrated = await migrateLegacySettings();
+      const settings = migrated ?? await invoke<AppearanceSettings>('get_settings');
+      get().syncFromEvent(settings);
+    } catch (e) {
+      console.warn('Failed to load settings from backend', e);
+    }
+  },
+  syncFromEvent: (settings) => {
+    const currentAppearance = get().appearance;
+    // Simple shallow comparison to prevent unnecessary updates
+    const hasChanged = Object.keys(settings).some(key => 
+      currentAppearance[key as keyof AppearanceSettings] !== settings[key as keyof AppearanceSettings]
+    );
+    if (hasChanged) {
+      set(() => ({ appearance: { ...settings } }));
     }
-  )
-);
+  },
+}));